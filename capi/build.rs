@@ -0,0 +1,19 @@
+use std::{env, path::PathBuf};
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    // The header is generated in `OUT_DIR` so that the source directory is
+    // never written to. The tests check that `include/sliceslice.h` matches it.
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let header = out_path.join("sliceslice.h");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(cbindgen::Config::from_root_or_default(&crate_dir))
+        .generate()
+        .unwrap()
+        .write_to_file(&header);
+    println!("cargo:rustc-env=SLICESLICE_HEADER={}", header.display());
+}
@@ -0,0 +1,18 @@
+use std::{env, path::PathBuf};
+
+fn main() {
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    // The C test program is only linked into the integration tests, which
+    // declare it with `#[link]`, so that it does not end up in the library.
+    println!("cargo:rerun-if-changed=tests/capi.c");
+    println!("cargo:rerun-if-changed=../include/sliceslice.h");
+    cc::Build::new()
+        .file("tests/capi.c")
+        .include("../include")
+        .flag_if_supported("-std=c99")
+        .warnings_into_errors(true)
+        .cargo_metadata(false)
+        .compile("capi_test");
+    println!("cargo:rustc-link-search=native={}", out_path.display());
+}
@@ -0,0 +1,2 @@
+//! Test harness compiling the C test program of `sliceslice-capi` against its
+//! header, so that building the C API itself does not require a C compiler.
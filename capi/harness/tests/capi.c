@@ -0,0 +1,100 @@
+#include <string.h>
+
+#include "sliceslice.h"
+
+/* Returns the line of the first failed check, so that it can be reported by
+ * the Rust test harness. */
+#define CHECK(condition)                                                       \
+    do {                                                                       \
+        if (!(condition)) {                                                    \
+            return __LINE__;                                                   \
+        }                                                                      \
+    } while (0)
+
+static int test_find(void) {
+    const char *needle = "ipsum";
+    const char *haystack = "Lorem ipsum dolor sit amet";
+    size_t position = 0;
+
+    ss_searcher *searcher =
+        ss_searcher_new((const uint8_t *)needle, strlen(needle));
+    CHECK(searcher != NULL);
+
+    CHECK(ss_find(searcher, (const uint8_t *)haystack, strlen(haystack),
+                  &position));
+    CHECK(position == 6);
+
+    CHECK(!ss_find(searcher, (const uint8_t *)haystack, 10, &position));
+    CHECK(!ss_find(searcher, NULL, 0, &position));
+
+    ss_searcher_free(searcher);
+    return 0;
+}
+
+static int test_find_iter(void) {
+    const char *needle = "aa";
+    const char *haystack = "aaaaa baa";
+    const size_t expected[] = {0, 2, 7};
+    size_t count = 0;
+    size_t cursor = 0;
+    size_t position = 0;
+
+    ss_searcher *searcher =
+        ss_searcher_new((const uint8_t *)needle, strlen(needle));
+    CHECK(searcher != NULL);
+
+    while (ss_find_iter_next(searcher, (const uint8_t *)haystack,
+                             strlen(haystack), &cursor, &position)) {
+        CHECK(count < 3);
+        CHECK(position == expected[count]);
+        count++;
+    }
+    CHECK(count == 3);
+
+    /* The iteration stays finished once no more matches are found. */
+    CHECK(!ss_find_iter_next(searcher, (const uint8_t *)haystack,
+                             strlen(haystack), &cursor, &position));
+
+    ss_searcher_free(searcher);
+    return 0;
+}
+
+static int test_empty_needle(void) {
+    const char *haystack = "abc";
+    size_t count = 0;
+    size_t cursor = 0;
+    size_t position = 0;
+
+    ss_searcher *searcher = ss_searcher_new(NULL, 0);
+    CHECK(searcher != NULL);
+
+    CHECK(ss_find(searcher, (const uint8_t *)haystack, strlen(haystack),
+                  &position));
+    CHECK(position == 0);
+
+    while (ss_find_iter_next(searcher, (const uint8_t *)haystack,
+                             strlen(haystack), &cursor, &position)) {
+        CHECK(position == count);
+        count++;
+    }
+    CHECK(count == 4);
+
+    ss_searcher_free(searcher);
+    ss_searcher_free(NULL);
+    return 0;
+}
+
+int ss_capi_test(void) {
+    int line;
+
+    if ((line = test_find()) != 0) {
+        return line;
+    }
+    if ((line = test_find_iter()) != 0) {
+        return line;
+    }
+    if ((line = test_empty_needle()) != 0) {
+        return line;
+    }
+    return 0;
+}
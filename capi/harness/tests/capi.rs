@@ -0,0 +1,15 @@
+use std::os::raw::c_int;
+
+// Makes sure the C API is linked into the test binary.
+use sliceslice_capi as _;
+
+#[link(name = "capi_test", kind = "static")]
+extern "C" {
+    fn ss_capi_test() -> c_int;
+}
+
+#[test]
+fn capi() {
+    let line = unsafe { ss_capi_test() };
+    assert_eq!(line, 0, "check failed at tests/capi.c:{line}");
+}
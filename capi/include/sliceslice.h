@@ -0,0 +1,69 @@
+#ifndef SLICESLICE_H
+#define SLICESLICE_H
+
+/* This file is generated by build.rs, do not edit it manually. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Opaque handle to a searcher for a single needle, using the fastest backend
+// available at runtime.
+typedef struct ss_searcher ss_searcher;
+
+// Creates a new searcher for the `needle_len` bytes pointed to by `needle`,
+// which are copied so the needle does not need to outlive the searcher.
+//
+// The returned searcher must be destroyed with `ss_searcher_free`.
+//
+// # Safety
+//
+// `needle` must point to `needle_len` readable bytes, or may be null if
+// `needle_len` is zero.
+ss_searcher *ss_searcher_new(const uint8_t *needle, size_t needle_len);
+
+// Searches for the leftmost match of the needle within the `haystack_len`
+// bytes pointed to by `haystack`. Returns whether a match was found, in which
+// case its position is stored in `position`.
+//
+// # Safety
+//
+// `searcher` must have been returned by `ss_searcher_new` and not freed yet.
+// `haystack` must point to `haystack_len` readable bytes, or may be null if
+// `haystack_len` is zero. `position` must point to a writable `size_t`.
+bool ss_find(const ss_searcher *searcher,
+             const uint8_t *haystack,
+             size_t haystack_len,
+             size_t *position);
+
+// Searches for the next non-overlapping match of the needle within the
+// `haystack_len` bytes pointed to by `haystack`, starting from `*cursor`.
+// Returns whether a match was found, in which case its position is stored in
+// `position` and `cursor` is moved past it.
+//
+// Iterating over all the matches is done by setting `cursor` to zero and
+// calling this function until it returns `false`, with the same semantics as
+// `memchr::memmem::find_iter`.
+//
+// # Safety
+//
+// `searcher` must have been returned by `ss_searcher_new` and not freed yet.
+// `haystack` must point to `haystack_len` readable bytes, or may be null if
+// `haystack_len` is zero. `cursor` and `position` must point to writable
+// `size_t` values.
+bool ss_find_iter_next(const ss_searcher *searcher,
+                       const uint8_t *haystack,
+                       size_t haystack_len,
+                       size_t *cursor,
+                       size_t *position);
+
+// Destroys a searcher returned by `ss_searcher_new`. Does nothing if
+// `searcher` is null.
+//
+// # Safety
+//
+// `searcher` must have been returned by `ss_searcher_new` and not freed yet,
+// or be null.
+void ss_searcher_free(ss_searcher *searcher);
+
+#endif /* SLICESLICE_H */
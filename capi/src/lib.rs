@@ -0,0 +1,128 @@
+//! C API for the sliceslice substring search library.
+//!
+//! Searchers are exposed as opaque handles created with `ss_searcher_new` and
+//! destroyed with `ss_searcher_free`. The matching C header is
+//! `include/sliceslice.h`, which is checked against the one generated by the
+//! build script.
+
+#![allow(non_camel_case_types)]
+
+use sliceslice::memmem::Finder;
+use std::slice;
+
+/// Opaque handle to a searcher for a single needle, using the fastest backend
+/// available at runtime.
+pub struct ss_searcher {
+    finder: Finder<'static>,
+}
+
+/// Returns a slice from a pointer and a length, allowing null pointers for
+/// empty slices.
+unsafe fn bytes<'a>(pointer: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(pointer, len)
+    }
+}
+
+/// Creates a new searcher for the `needle_len` bytes pointed to by `needle`,
+/// which are copied so the needle does not need to outlive the searcher.
+///
+/// The returned searcher must be destroyed with `ss_searcher_free`.
+///
+/// # Safety
+///
+/// `needle` must point to `needle_len` readable bytes, or may be null if
+/// `needle_len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn ss_searcher_new(needle: *const u8, needle_len: usize) -> *mut ss_searcher {
+    let searcher = ss_searcher {
+        finder: Finder::new(bytes(needle, needle_len)).into_owned(),
+    };
+    Box::into_raw(Box::new(searcher))
+}
+
+/// Searches for the leftmost match of the needle within the `haystack_len`
+/// bytes pointed to by `haystack`. Returns whether a match was found, in which
+/// case its position is stored in `position`.
+///
+/// # Safety
+///
+/// `searcher` must have been returned by `ss_searcher_new` and not freed yet.
+/// `haystack` must point to `haystack_len` readable bytes, or may be null if
+/// `haystack_len` is zero. `position` must point to a writable `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn ss_find(
+    searcher: *const ss_searcher,
+    haystack: *const u8,
+    haystack_len: usize,
+    position: *mut usize,
+) -> bool {
+    match (*searcher).finder.find(bytes(haystack, haystack_len)) {
+        Some(found) => {
+            *position = found;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Searches for the next non-overlapping match of the needle within the
+/// `haystack_len` bytes pointed to by `haystack`, starting from `*cursor`.
+/// Returns whether a match was found, in which case its position is stored in
+/// `position` and `cursor` is moved past it.
+///
+/// Iterating over all the matches is done by setting `cursor` to zero and
+/// calling this function until it returns `false`, with the same semantics as
+/// `memchr::memmem::find_iter`.
+///
+/// # Safety
+///
+/// `searcher` must have been returned by `ss_searcher_new` and not freed yet.
+/// `haystack` must point to `haystack_len` readable bytes, or may be null if
+/// `haystack_len` is zero. `cursor` and `position` must point to writable
+/// `size_t` values.
+#[no_mangle]
+pub unsafe extern "C" fn ss_find_iter_next(
+    searcher: *const ss_searcher,
+    haystack: *const u8,
+    haystack_len: usize,
+    cursor: *mut usize,
+    position: *mut usize,
+) -> bool {
+    let start = *cursor;
+    if start > haystack_len {
+        return false;
+    }
+
+    let finder = &(*searcher).finder;
+    let haystack = bytes(haystack, haystack_len);
+    match finder.find(&haystack[start..]) {
+        Some(found) => {
+            let found = start + found;
+            // Empty needles match at every position, so always move forward.
+            *cursor = found + finder.needle().len().max(1);
+            *position = found;
+            true
+        }
+        None => {
+            *cursor = haystack_len + 1;
+            false
+        }
+    }
+}
+
+/// Destroys a searcher returned by `ss_searcher_new`. Does nothing if
+/// `searcher` is null.
+///
+/// # Safety
+///
+/// `searcher` must have been returned by `ss_searcher_new` and not freed yet,
+/// or be null.
+#[no_mangle]
+pub unsafe extern "C" fn ss_searcher_free(searcher: *mut ss_searcher) {
+    if !searcher.is_null() {
+        drop(Box::from_raw(searcher));
+    }
+}
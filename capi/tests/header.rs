@@ -0,0 +1,12 @@
+use std::fs;
+
+#[test]
+fn header_up_to_date() {
+    let generated = fs::read_to_string(env!("SLICESLICE_HEADER")).unwrap();
+    let committed = include_str!("../include/sliceslice.h");
+    assert!(
+        generated == committed,
+        "include/sliceslice.h is out of date, copy it from {}",
+        env!("SLICESLICE_HEADER"),
+    );
+}
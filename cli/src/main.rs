@@ -0,0 +1,337 @@
+//! Command line tool searching files for lines containing fixed strings, using
+//! the fastest SIMD backend available at runtime.
+
+mod search;
+
+use clap::{Parser, ValueEnum};
+use memmap2::Mmap;
+use search::{Line, LineSearcher, Options, STREAM_CAPACITY};
+use sliceslice::builder::{Backend, Probe, SearcherBuilder};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use walkdir::WalkDir;
+
+/// Backend selected with `--backend`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum BackendArg {
+    /// Fastest backend available at runtime.
+    Auto,
+    /// AVX2 on x86 processors.
+    Avx2,
+    /// NEON on aarch64 processors.
+    Neon,
+    /// SIMD128 on wasm32.
+    Wasm32,
+    /// Portable SIMD, requires the `stdsimd` feature of sliceslice.
+    Stdsimd,
+    /// Scalar search using memchr.
+    Scalar,
+}
+
+impl From<BackendArg> for Backend {
+    fn from(backend: BackendArg) -> Self {
+        match backend {
+            BackendArg::Auto => Self::Auto,
+            BackendArg::Avx2 => Self::Avx2,
+            BackendArg::Neon => Self::Neon,
+            BackendArg::Wasm32 => Self::Wasm32,
+            BackendArg::Stdsimd => Self::StdSimd,
+            BackendArg::Scalar => Self::Scalar,
+        }
+    }
+}
+
+/// Searches files, directories and standard input for lines containing any of
+/// the given needles.
+#[derive(Debug, Parser)]
+#[command(name = "sliceslice", version)]
+struct Args {
+    /// Needle to search for, unless given with `-e`. Newlines separate multiple
+    /// needles.
+    #[arg(required_unless_present = "needles")]
+    needle: Option<OsString>,
+
+    /// Files or directories to search recursively. Standard input is searched
+    /// if none is given or for `-`.
+    paths: Vec<PathBuf>,
+
+    /// Needle to search for, which may be given multiple times.
+    #[arg(short = 'e', long = "needle", value_name = "NEEDLE")]
+    needles: Vec<OsString>,
+
+    /// Matches needles ignoring ASCII case.
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Selects the lines which do not contain any needle.
+    #[arg(short = 'v', long)]
+    invert_match: bool,
+
+    /// Prints the line number of each line.
+    #[arg(short = 'n', long)]
+    line_number: bool,
+
+    /// Prints the byte offset of the start of each line.
+    #[arg(short = 'b', long)]
+    byte_offset: bool,
+
+    /// Only prints the number of selected lines of each input.
+    #[arg(short, long, conflicts_with_all = ["line_number", "byte_offset"])]
+    count: bool,
+
+    /// Backend used to search for needles.
+    #[arg(long, value_enum, default_value_t = BackendArg::Auto)]
+    backend: BackendArg,
+
+    /// Index of the needle byte compared alongside the first one to filter
+    /// candidate positions. Defaults to the last byte.
+    #[arg(long)]
+    position: Option<usize>,
+
+    /// Reads regular files instead of mapping them into memory.
+    #[arg(long)]
+    no_mmap: bool,
+}
+
+/// Input to search, with the name printed alongside its lines.
+enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    fn name(&self) -> String {
+        match self {
+            Self::Stdin => "(standard input)".to_owned(),
+            Self::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// Writes selected lines or counts to the output.
+struct Printer<W: Write> {
+    output: W,
+    with_filename: bool,
+    line_number: bool,
+    byte_offset: bool,
+    count: bool,
+}
+
+impl<W: Write> Printer<W> {
+    fn line(&mut self, name: &str, line: Line<'_>) -> io::Result<()> {
+        if self.with_filename {
+            write!(self.output, "{name}:")?;
+        }
+        if self.line_number {
+            write!(self.output, "{}:", line.number)?;
+        }
+        if self.byte_offset {
+            write!(self.output, "{}:", line.offset)?;
+        }
+        self.output.write_all(line.bytes)?;
+        self.output.write_all(b"\n")
+    }
+
+    fn count(&mut self, name: &str, count: u64) -> io::Result<()> {
+        if self.with_filename {
+            write!(self.output, "{name}:")?;
+        }
+        writeln!(self.output, "{count}")
+    }
+}
+
+/// Error while searching an input, which is either reported before moving to
+/// the next input or aborts the search if the output failed.
+enum SearchError {
+    Input(io::Error),
+    Output(io::Error),
+}
+
+impl From<io::Error> for SearchError {
+    fn from(error: io::Error) -> Self {
+        Self::Input(error)
+    }
+}
+
+/// Search of inputs, printing the lines selected by a line searcher.
+struct Search<W: Write> {
+    searcher: LineSearcher,
+    printer: Printer<W>,
+    no_mmap: bool,
+}
+
+impl<W: Write> Search<W> {
+    /// Searches `input` and returns whether any line was selected.
+    fn input(&mut self, input: &Input) -> Result<bool, SearchError> {
+        let name = input.name();
+        let count = self.printer.count;
+        let mut selected = 0;
+
+        let printer = &mut self.printer;
+        let report = |line: Line<'_>| {
+            selected += 1;
+            if count {
+                return Ok(());
+            }
+            printer.line(&name, line).map_err(SearchError::Output)
+        };
+
+        match input {
+            Input::Stdin => {
+                self.searcher
+                    .search_reader(io::stdin().lock(), STREAM_CAPACITY, report)?
+            }
+            Input::File(path) => {
+                let file = File::open(path)?;
+
+                // Pipes and other special files can only be streamed, and
+                // mapping can fail on some file systems.
+                let mmap = match file.metadata()?.is_file() && !self.no_mmap {
+                    true => unsafe { Mmap::map(&file) }.ok(),
+                    false => None,
+                };
+                match mmap {
+                    Some(mmap) => self.searcher.search_slice(&mmap, report)?,
+                    None => self.searcher.search_reader(file, STREAM_CAPACITY, report)?,
+                }
+            }
+        }
+
+        if count {
+            self.printer
+                .count(&name, selected)
+                .map_err(SearchError::Output)?;
+        }
+        Ok(selected > 0)
+    }
+}
+
+/// Returns the inputs corresponding to `paths`, walking directories
+/// recursively in file name order. Errors are reported as they occur.
+fn inputs(paths: &[PathBuf], errors: &mut bool) -> Vec<Input> {
+    if paths.is_empty() {
+        return vec![Input::Stdin];
+    }
+
+    let mut inputs = Vec::new();
+    for path in paths {
+        if path == Path::new("-") {
+            inputs.push(Input::Stdin);
+            continue;
+        }
+
+        if !path.is_dir() {
+            inputs.push(Input::File(path.clone()));
+            continue;
+        }
+
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            match entry {
+                Ok(entry) if entry.file_type().is_file() => {
+                    inputs.push(Input::File(entry.into_path()))
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    eprintln!("sliceslice: {error}");
+                    *errors = true;
+                }
+            }
+        }
+    }
+    inputs
+}
+
+fn main() -> ExitCode {
+    let mut args = Args::parse();
+
+    // With needles given using `-e`, the first positional argument is a path.
+    let needles = if args.needles.is_empty() {
+        vec![args.needle.take().unwrap()]
+    } else {
+        if let Some(path) = args.needle.take() {
+            args.paths.insert(0, path.into());
+        }
+        args.needles
+    };
+    let needles: Vec<Vec<u8>> = needles
+        .into_iter()
+        .flat_map(|needle| {
+            let needle = needle.into_encoded_bytes();
+            needle
+                .split(|&b| b == b'\n')
+                .map(<[u8]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let mut builder = SearcherBuilder::new();
+    builder.backend(args.backend.into());
+    builder.ascii_case_insensitive(args.ignore_case);
+    if let Some(position) = args.position {
+        builder.probe(Probe::Position(position));
+    }
+
+    let options = Options {
+        invert_match: args.invert_match,
+        line_number: args.line_number,
+    };
+    let searcher = match LineSearcher::new(&needles, &builder, options) {
+        Ok(searcher) => searcher,
+        Err(error) => {
+            eprintln!("sliceslice: {error}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut errors = false;
+    let with_filename = args.paths.len() > 1 || args.paths.iter().any(|path| path.is_dir());
+    let inputs = inputs(&args.paths, &mut errors);
+
+    let mut search = Search {
+        searcher,
+        printer: Printer {
+            output: BufWriter::new(io::stdout().lock()),
+            with_filename,
+            line_number: args.line_number,
+            byte_offset: args.byte_offset,
+            count: args.count,
+        },
+        no_mmap: args.no_mmap,
+    };
+
+    let mut matched = false;
+    for input in &inputs {
+        match search.input(input) {
+            Ok(selected) => matched |= selected,
+            Err(SearchError::Input(error)) => {
+                eprintln!("sliceslice: {}: {error}", input.name());
+                errors = true;
+            }
+            // The reader of the output went away, as when piping into `head`.
+            Err(SearchError::Output(error)) if error.kind() == ErrorKind::BrokenPipe => {
+                return ExitCode::SUCCESS;
+            }
+            Err(SearchError::Output(error)) => {
+                eprintln!("sliceslice: {error}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    if let Err(error) = search.printer.output.flush() {
+        if error.kind() != ErrorKind::BrokenPipe {
+            eprintln!("sliceslice: {error}");
+            return ExitCode::from(2);
+        }
+    }
+
+    // Same exit status as grep.
+    match (errors, matched) {
+        (true, _) => ExitCode::from(2),
+        (false, true) => ExitCode::SUCCESS,
+        (false, false) => ExitCode::from(1),
+    }
+}
@@ -0,0 +1,366 @@
+use memchr::{memchr, memchr_iter, memrchr};
+use sliceslice::builder::SearcherBuilder;
+use sliceslice::memmem::Finder;
+use sliceslice::{SearcherError, SubstringSearch};
+use std::io::{self, ErrorKind, Read};
+
+/// Initial size of the buffer used to search streams, which grows to fit the
+/// longest line.
+pub const STREAM_CAPACITY: usize = 64 * 1024;
+
+/// Options controlling which lines are selected and what is reported about
+/// them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    /// Whether lines not matching any needle are selected instead.
+    pub invert_match: bool,
+    /// Whether line numbers are computed.
+    pub line_number: bool,
+}
+
+/// Line selected by a `LineSearcher`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// Contents of the line, without its terminating newline.
+    pub bytes: &'a [u8],
+    /// Byte offset of the start of the line within the input.
+    pub offset: u64,
+    /// One-based line number, or zero when line numbers are not computed.
+    pub number: u64,
+}
+
+/// Position within the input of the start of the region being searched.
+#[derive(Clone, Copy, Debug)]
+struct Cursor {
+    offset: u64,
+    number: u64,
+}
+
+/// Searcher reporting the lines which contain any of several needles.
+pub struct LineSearcher {
+    searchers: Vec<Box<dyn SubstringSearch + Send + Sync>>,
+    options: Options,
+}
+
+impl LineSearcher {
+    /// Creates a new line searcher for `needles` configured using `builder`,
+    /// which also determines whether case is ignored. Empty needles match every
+    /// line.
+    pub fn new<N: AsRef<[u8]>>(
+        needles: &[N],
+        builder: &SearcherBuilder,
+        options: Options,
+    ) -> Result<Self, SearcherError> {
+        let mut searchers: Vec<Box<dyn SubstringSearch + Send + Sync>> = Vec::new();
+        for needle in needles {
+            let needle = needle.as_ref();
+
+            // Searchers do not support empty needles, which are instead found
+            // at the start of every haystack.
+            if needle.is_empty() {
+                searchers.push(Box::new(Finder::new(b"")));
+            } else {
+                searchers.push(Box::new(builder.build(Box::<[u8]>::from(needle))?));
+            }
+        }
+
+        Ok(Self { searchers, options })
+    }
+
+    /// Calls `f` with every selected line of `haystack`, in order, stopping at
+    /// the first error.
+    pub fn search_slice<E, F: FnMut(Line<'_>) -> Result<(), E>>(
+        &self,
+        haystack: &[u8],
+        mut f: F,
+    ) -> Result<(), E> {
+        let cursor = Cursor {
+            offset: 0,
+            number: 1,
+        };
+        self.search_lines(haystack, cursor, &mut f)
+    }
+
+    /// Same as `search_slice` but reads the haystack from `reader`, keeping
+    /// only the lines being searched in memory. Read errors are converted into
+    /// the error type of `f`.
+    pub fn search_reader<R, E, F>(&self, mut reader: R, capacity: usize, mut f: F) -> Result<(), E>
+    where
+        R: Read,
+        E: From<io::Error>,
+        F: FnMut(Line<'_>) -> Result<(), E>,
+    {
+        let mut buffer = vec![0; capacity.max(1)];
+        let mut len = 0;
+        let mut cursor = Cursor {
+            offset: 0,
+            number: 1,
+        };
+
+        loop {
+            if len == buffer.len() {
+                buffer.resize(2 * len, 0);
+            }
+
+            let read = match reader.read(&mut buffer[len..]) {
+                Ok(read) => read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            };
+            if read == 0 {
+                return self.search_lines(&buffer[..len], cursor, &mut f);
+            }
+
+            // Only whole lines are searched, the last partial one being kept
+            // for the next read. Previously read bytes contain no newline.
+            let start = len;
+            len += read;
+            if let Some(i) = memrchr(b'\n', &buffer[start..len]) {
+                let end = start + i + 1;
+                self.search_lines(&buffer[..end], cursor, &mut f)?;
+                cursor = self.advance(cursor, &buffer[..end]);
+                buffer.copy_within(end..len, 0);
+                len -= end;
+            }
+        }
+    }
+
+    /// Returns the cursor positioned after `lines`.
+    fn advance(&self, cursor: Cursor, lines: &[u8]) -> Cursor {
+        let number = if self.options.line_number {
+            cursor.number + memchr_iter(b'\n', lines).count() as u64
+        } else {
+            0
+        };
+
+        Cursor {
+            offset: cursor.offset + lines.len() as u64,
+            number,
+        }
+    }
+
+    /// Searches whole `lines` starting at `cursor` within the input.
+    fn search_lines<E, F: FnMut(Line<'_>) -> Result<(), E>>(
+        &self,
+        lines: &[u8],
+        cursor: Cursor,
+        f: &mut F,
+    ) -> Result<(), E> {
+        let mut reporter = Reporter {
+            lines,
+            cursor,
+            counted: 0,
+            line_number: self.options.line_number,
+        };
+
+        // Positions of the next match of each needle, refreshed once passed.
+        let mut next: Vec<Option<usize>> = self.searchers.iter().map(|s| s.find(lines)).collect();
+
+        // There is no line after a trailing newline, so matches of empty
+        // needles at the very end are never reached.
+        let mut start = 0;
+        while start < lines.len() {
+            let mut earliest: Option<usize> = None;
+            for (searcher, next) in self.searchers.iter().zip(&mut next) {
+                if next.is_some_and(|position| position < start) {
+                    *next = searcher.find(&lines[start..]).map(|i| start + i);
+                }
+                earliest = match (earliest, *next) {
+                    (Some(earliest), Some(next)) => Some(earliest.min(next)),
+                    (earliest, next) => earliest.or(next),
+                };
+            }
+
+            let Some(position) = earliest else {
+                break;
+            };
+            let line_start = memrchr(b'\n', &lines[..position]).map_or(0, |i| i + 1);
+            let line_end = memchr(b'\n', &lines[position..]).map_or(lines.len(), |i| position + i);
+
+            if self.options.invert_match {
+                reporter.report_range(start, line_start, f)?;
+            } else {
+                reporter.report(line_start, line_end, f)?;
+            }
+            start = line_end + 1;
+        }
+
+        if self.options.invert_match && start < lines.len() {
+            reporter.report_range(start, lines.len(), f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reports lines to a callback, computing their line numbers lazily.
+struct Reporter<'a> {
+    lines: &'a [u8],
+    cursor: Cursor,
+    counted: usize,
+    line_number: bool,
+}
+
+impl Reporter<'_> {
+    /// Reports the line spanning `lines[start..end]`.
+    fn report<E, F: FnMut(Line<'_>) -> Result<(), E>>(
+        &mut self,
+        start: usize,
+        end: usize,
+        f: &mut F,
+    ) -> Result<(), E> {
+        let number = if self.line_number {
+            let newlines = memchr_iter(b'\n', &self.lines[self.counted..start]).count();
+            self.cursor.number += newlines as u64;
+            self.counted = start;
+            self.cursor.number
+        } else {
+            0
+        };
+
+        f(Line {
+            bytes: &self.lines[start..end],
+            offset: self.cursor.offset + start as u64,
+            number,
+        })
+    }
+
+    /// Reports every line within `lines[start..end]`, where `start` is the
+    /// start of a line and `end` is either the start of a line or the end of
+    /// the lines.
+    fn report_range<E, F: FnMut(Line<'_>) -> Result<(), E>>(
+        &mut self,
+        mut start: usize,
+        end: usize,
+        f: &mut F,
+    ) -> Result<(), E> {
+        while start < end {
+            let line_end = memchr(b'\n', &self.lines[start..end]).map_or(end, |i| start + i);
+            self.report(start, line_end, f)?;
+            start = line_end + 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Line, LineSearcher, Options};
+    use sliceslice::builder::SearcherBuilder;
+    use std::convert::Infallible;
+    use std::io;
+
+    static WORDS: &[u8] = include_bytes!("../../data/words.txt");
+
+    /// Returns the lines selected by a naive implementation.
+    fn expected(
+        haystack: &[u8],
+        needles: &[&[u8]],
+        ignore_case: bool,
+        options: Options,
+    ) -> Vec<(u64, u64, Vec<u8>)> {
+        let mut lines: Vec<&[u8]> = haystack.split(|&b| b == b'\n').collect();
+        if haystack.is_empty() || haystack.ends_with(b"\n") {
+            lines.pop();
+        }
+
+        let mut offset = 0;
+        let mut selected = Vec::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            let text = if ignore_case {
+                line.to_ascii_lowercase()
+            } else {
+                line.to_vec()
+            };
+            let matched = needles.iter().any(|needle| {
+                let needle = if ignore_case {
+                    needle.to_ascii_lowercase()
+                } else {
+                    needle.to_vec()
+                };
+                needle.is_empty() || text.windows(needle.len()).any(|window| window == needle)
+            });
+            if matched != options.invert_match {
+                let number = if options.line_number { i as u64 + 1 } else { 0 };
+                selected.push((offset, number, line.to_vec()));
+            }
+            offset += line.len() as u64 + 1;
+        }
+        selected
+    }
+
+    fn search(haystack: &[u8], needles: &[&[u8]]) {
+        for ignore_case in [false, true] {
+            for invert_match in [false, true] {
+                for line_number in [false, true] {
+                    let options = Options {
+                        invert_match,
+                        line_number,
+                    };
+                    let mut builder = SearcherBuilder::new();
+                    builder.ascii_case_insensitive(ignore_case);
+                    let searcher = LineSearcher::new(needles, &builder, options).unwrap();
+                    let expected = expected(haystack, needles, ignore_case, options);
+                    let to_tuple = |line: Line<'_>| (line.offset, line.number, line.bytes.to_vec());
+
+                    let mut selected = Vec::new();
+                    searcher
+                        .search_slice(haystack, |line| {
+                            selected.push(to_tuple(line));
+                            Ok::<_, Infallible>(())
+                        })
+                        .unwrap();
+                    assert_eq!(selected, expected, "{needles:?} {ignore_case} {options:?}");
+
+                    for capacity in [1, 7, 4096] {
+                        let mut selected = Vec::new();
+                        searcher
+                            .search_reader(haystack, capacity, |line| {
+                                selected.push(to_tuple(line));
+                                Ok::<_, io::Error>(())
+                            })
+                            .unwrap();
+                        assert_eq!(
+                            selected, expected,
+                            "{needles:?} {ignore_case} {options:?} {capacity}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn line_search() {
+        let haystacks: [&[u8]; 8] = [
+            b"",
+            b"\n",
+            b"\n\n",
+            b"abc",
+            b"abc\n",
+            b"abc\ndef\nABC\n\nxyz",
+            b"foo bar\nbaz\nfoo\nqux\n",
+            b"aaaa\naa\na\n\naaa",
+        ];
+        let needles: [&[&[u8]]; 7] = [
+            &[b""],
+            &[b"a"],
+            &[b"aa"],
+            &[b"abc"],
+            &[b"foo", b"qux"],
+            &[b"zzz"],
+            &[b"x", b"", b"b"],
+        ];
+
+        for haystack in haystacks {
+            for needles in needles {
+                search(haystack, needles);
+            }
+        }
+    }
+
+    #[test]
+    fn line_search_words() {
+        search(WORDS, &[b"ation"]);
+        search(WORDS, &[b"ZZ", b"qu", b"xylo"]);
+    }
+}
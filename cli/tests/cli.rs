@@ -0,0 +1,133 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use tempfile::TempDir;
+
+static WORDS: &str = include_str!("../../data/words.txt");
+
+/// Runs the binary with `args` from `dir`, writing `stdin` to its standard
+/// input.
+fn run(dir: &Path, args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sliceslice"))
+        .current_dir(dir)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Returns the standard output of a successful run.
+fn stdout(dir: &Path, args: &[&str]) -> String {
+    let output = run(dir, args, b"");
+    assert_eq!(output.status.code(), Some(0), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Creates a temporary directory containing a few files.
+fn files() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "foo\nbar\nFoo baz\n").unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub/b.txt"), "qux\nfoobar").unwrap();
+    fs::write(dir.path().join("sub/empty.txt"), "").unwrap();
+    dir
+}
+
+#[test]
+fn cli_search_file() {
+    let dir = files();
+    let path = dir.path();
+
+    assert_eq!(stdout(path, &["foo", "a.txt"]), "foo\n");
+    assert_eq!(stdout(path, &["-i", "foo", "a.txt"]), "foo\nFoo baz\n");
+    assert_eq!(stdout(path, &["-v", "foo", "a.txt"]), "bar\nFoo baz\n");
+    assert_eq!(stdout(path, &["-n", "ba", "a.txt"]), "2:bar\n3:Foo baz\n");
+    assert_eq!(stdout(path, &["-b", "ba", "a.txt"]), "4:bar\n8:Foo baz\n");
+    assert_eq!(stdout(path, &["-c", "o", "a.txt"]), "2\n");
+    assert_eq!(
+        stdout(path, &["-e", "bar", "-e", "baz", "a.txt"]),
+        "bar\nFoo baz\n"
+    );
+    assert_eq!(stdout(path, &["bar\nbaz", "a.txt"]), "bar\nFoo baz\n");
+}
+
+#[test]
+fn cli_search_directory() {
+    let dir = files();
+    let path = dir.path();
+
+    assert_eq!(
+        stdout(path, &["-n", "foo", "."]),
+        "./a.txt:1:foo\n./sub/b.txt:2:foobar\n"
+    );
+    assert_eq!(
+        stdout(path, &["-c", "foo", "a.txt", "sub"]),
+        "a.txt:1\nsub/b.txt:1\nsub/empty.txt:0\n"
+    );
+}
+
+#[test]
+fn cli_search_stdin() {
+    let dir = files();
+    let path = dir.path();
+
+    let output = run(path, &["-n", "foo"], b"foo\nbar\nfoo");
+    assert_eq!(output.stdout, b"1:foo\n3:foo\n");
+
+    let output = run(path, &["-c", "foo", "-", "a.txt"], b"foo\nfoo\n");
+    assert_eq!(output.stdout, b"(standard input):2\na.txt:1\n");
+}
+
+#[test]
+fn cli_search_words() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path();
+    fs::write(path.join("words.txt"), WORDS).unwrap();
+
+    let expected: String = WORDS
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains("ation") || line.contains("xylo"))
+        .map(|(i, line)| format!("{}:{line}\n", i + 1))
+        .collect();
+
+    let args = ["-n", "-e", "ation", "-e", "xylo", "words.txt"];
+    assert_eq!(stdout(path, &args), expected);
+    assert_eq!(
+        stdout(path, &[&args[..], &["--no-mmap"]].concat()),
+        expected
+    );
+    assert_eq!(
+        stdout(path, &[&args[..], &["--backend", "scalar"]].concat()),
+        expected
+    );
+    assert_eq!(
+        stdout(path, &[&args[..], &["--position", "1"]].concat()),
+        expected
+    );
+
+    let output = run(path, &args[..5], WORDS.as_bytes());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn cli_exit_status() {
+    let dir = files();
+    let path = dir.path();
+
+    assert_eq!(run(path, &["foo", "a.txt"], b"").status.code(), Some(0));
+    assert_eq!(run(path, &["zzz", "a.txt"], b"").status.code(), Some(1));
+
+    let output = run(path, &["foo", "a.txt", "missing.txt"], b"");
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(output.stdout, b"a.txt:foo\n");
+
+    let output = run(path, &["--position", "5", "foo", "a.txt"], b"");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,109 @@
+//! Python bindings for the sliceslice substring search library.
+//!
+//! Haystacks may be any object supporting the buffer protocol, such as
+//! `bytes`, `bytearray`, `memoryview` or `mmap.mmap`, and are searched without
+//! copying them. Searches always release the GIL.
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::PyBufferError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sliceslice::memmem::Finder;
+use std::slice;
+
+/// Calls `f` with the contents of the buffer exported by `object`, without
+/// holding the GIL.
+fn with_bytes<T: Send, F: FnOnce(&[u8]) -> T + Send>(
+    py: Python<'_>,
+    object: &Bound<'_, PyAny>,
+    f: F,
+) -> PyResult<T> {
+    let buffer = PyBuffer::<u8>::get(object)?;
+    if !buffer.is_c_contiguous() {
+        return Err(PyBufferError::new_err("buffer is not contiguous"));
+    }
+
+    // Exporting a buffer prevents its owner from resizing or freeing it until
+    // the buffer is released, which only happens once `buffer` is dropped.
+    let bytes = if buffer.len_bytes() == 0 {
+        &[][..]
+    } else {
+        unsafe { slice::from_raw_parts(buffer.buf_ptr().cast::<u8>(), buffer.len_bytes()) }
+    };
+
+    // The GIL is released even for writable buffers, since the read-only flag
+    // does not prevent the underlying memory from being mutated elsewhere, for
+    // example through the `bytearray` behind `memoryview.toreadonly()` or by
+    // another process sharing a memory mapping. Such concurrent mutations can
+    // only affect the results of the search, as the buffer stays allocated.
+    Ok(py.allow_threads(|| f(bytes)))
+}
+
+/// Searcher for a single needle, using the fastest SIMD backend available at
+/// runtime.
+///
+/// The needle and haystacks may be any object supporting the buffer protocol.
+/// Searches release the GIL, so a searcher may be shared between threads. The
+/// results of searching a haystack which is modified during the search, e.g.
+/// a `bytearray` or writable `mmap.mmap` written to by another thread, are
+/// unspecified.
+#[pyclass(frozen, module = "sliceslice")]
+struct Searcher {
+    finder: Finder<'static>,
+}
+
+#[pymethods]
+impl Searcher {
+    #[new]
+    fn new(py: Python<'_>, needle: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let finder = with_bytes(py, needle, |needle| Finder::new(needle).into_owned())?;
+        Ok(Self { finder })
+    }
+
+    /// The needle searched for.
+    #[getter]
+    fn needle(&self) -> &[u8] {
+        self.finder.needle()
+    }
+
+    /// Returns the position of the leftmost match within `haystack`, or `None`
+    /// if there is no match.
+    fn find(&self, py: Python<'_>, haystack: &Bound<'_, PyAny>) -> PyResult<Option<usize>> {
+        with_bytes(py, haystack, |haystack| self.finder.find(haystack))
+    }
+
+    /// Returns the positions of all the non-overlapping matches within
+    /// `haystack`, from left to right.
+    fn find_all(&self, py: Python<'_>, haystack: &Bound<'_, PyAny>) -> PyResult<Vec<usize>> {
+        with_bytes(py, haystack, |haystack| {
+            self.finder.find_iter(haystack).collect()
+        })
+    }
+
+    /// Returns the number of non-overlapping matches within `haystack`.
+    fn count(&self, py: Python<'_>, haystack: &Bound<'_, PyAny>) -> PyResult<usize> {
+        with_bytes(py, haystack, |haystack| {
+            self.finder.find_iter(haystack).count()
+        })
+    }
+
+    /// Returns whether `haystack` contains a match.
+    fn contains(&self, py: Python<'_>, haystack: &Bound<'_, PyAny>) -> PyResult<bool> {
+        with_bytes(py, haystack, |haystack| {
+            self.finder.find(haystack).is_some()
+        })
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let needle = PyBytes::new(py, self.finder.needle()).repr()?;
+        Ok(format!("Searcher({needle})"))
+    }
+}
+
+/// A fast implementation of single-pattern substring search using SIMD
+/// acceleration.
+#[pymodule]
+#[pyo3(name = "sliceslice")]
+fn sliceslice_python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Searcher>()
+}
@@ -0,0 +1,110 @@
+import mmap
+import tempfile
+import threading
+import unittest
+
+from sliceslice import Searcher
+
+HAYSTACK = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit, ipsum"
+
+
+def find_all(haystack, needle):
+    positions = []
+    start = 0
+    while True:
+        position = haystack.find(needle, start)
+        if position < 0:
+            return positions
+        positions.append(position)
+        start = position + max(len(needle), 1)
+
+
+class SearcherTest(unittest.TestCase):
+    def check(self, haystack, needle):
+        searcher = Searcher(needle)
+        expected = find_all(bytes(haystack), bytes(needle))
+
+        position = bytes(haystack).find(bytes(needle))
+        self.assertEqual(searcher.find(haystack), None if position < 0 else position)
+        self.assertEqual(searcher.find_all(haystack), expected)
+        self.assertEqual(searcher.count(haystack), len(expected))
+        self.assertEqual(searcher.contains(haystack), bytes(needle) in bytes(haystack))
+
+    def test_search(self):
+        haystacks = [b"", b"a", b"aaa", b"abab", HAYSTACK, HAYSTACK * 100]
+        needles = [b"", b"a", b"aa", b"ab", b"ipsum", b"consectetur", b"zzz"]
+
+        for haystack in haystacks:
+            for needle in needles:
+                with self.subTest(haystack=haystack[:16], needle=needle):
+                    self.check(haystack, needle)
+
+    def test_buffer_protocol(self):
+        needle = b"ipsum"
+        self.check(bytearray(HAYSTACK), needle)
+        self.check(memoryview(HAYSTACK), needle)
+        self.check(memoryview(HAYSTACK)[6:], needle)
+        self.check(HAYSTACK, bytearray(needle))
+        self.check(HAYSTACK, memoryview(needle))
+
+        with tempfile.TemporaryFile() as file:
+            file.write(HAYSTACK)
+            file.flush()
+            with mmap.mmap(file.fileno(), 0, access=mmap.ACCESS_READ) as mapped:
+                self.check(mapped, needle)
+
+    def test_non_contiguous(self):
+        searcher = Searcher(b"a")
+        with self.assertRaises(BufferError):
+            searcher.find(memoryview(HAYSTACK)[::2])
+
+    def test_invalid_type(self):
+        with self.assertRaises(TypeError):
+            Searcher("ipsum")
+        with self.assertRaises(TypeError):
+            Searcher(b"ipsum").find("Lorem ipsum")
+
+    def test_threads(self):
+        searcher = Searcher(b"ipsum")
+        haystack = HAYSTACK * 1000
+        results = []
+
+        def search():
+            results.append(searcher.count(haystack))
+
+        threads = [threading.Thread(target=search) for _ in range(8)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+
+        self.assertEqual(results, [2000] * 8)
+
+    def test_threads_writable_mmap(self):
+        searcher = Searcher(b"ipsum")
+        results = []
+
+        with tempfile.TemporaryFile() as file:
+            file.write(HAYSTACK * 1000)
+            file.flush()
+            with mmap.mmap(file.fileno(), 0, access=mmap.ACCESS_WRITE) as mapped:
+
+                def search():
+                    results.append(searcher.count(mapped))
+
+                threads = [threading.Thread(target=search) for _ in range(8)]
+                for thread in threads:
+                    thread.start()
+                for thread in threads:
+                    thread.join()
+
+        self.assertEqual(results, [2000] * 8)
+
+    def test_attributes(self):
+        searcher = Searcher(b"ipsum")
+        self.assertEqual(searcher.needle, b"ipsum")
+        self.assertEqual(repr(searcher), "Searcher(b'ipsum')")
+
+
+if __name__ == "__main__":
+    unittest.main()
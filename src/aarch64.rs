@@ -1,9 +1,9 @@
 #![allow(clippy::missing_safety_doc)]
 
-use crate::{Needle, NeedleWithSize, Searcher, Vector, VectorHash};
+use crate::{Needle, NeedleWithSize, Searcher, SearcherError, Vector, VectorHash, WordBoundary};
 
 #[cfg(target_arch = "aarch64")]
-use std::arch::aarch64::*;
+use core::arch::aarch64::*;
 
 static MD: [u8; 16] = [
     1 << 0,
@@ -204,55 +204,128 @@ impl<N: Needle> NeonSearcher<N> {
     /// `needle` or if the associated `SIZE` constant does not correspond to the
     /// actual size of `needle`.
     pub unsafe fn with_position(needle: N, position: usize) -> Self {
-        // Implicitly checks that the needle is not empty because position is an
-        // unsized integer.
-        assert!(position < needle.size());
+        match Self::try_with_position(needle, position) {
+            Ok(searcher) => searcher,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Same as `new` but returns an error instead of panicking if `needle` is
+    /// invalid.
+    pub unsafe fn try_new(needle: N) -> Result<Self, SearcherError> {
+        // Wrapping prevents panicking on unsigned integer underflow when
+        // `needle` is empty.
+        let position = needle.size().wrapping_sub(1);
+        Self::try_with_position(needle, position)
+    }
+
+    /// Same as `with_position` but returns an error instead of panicking if
+    /// `needle` or `position` is invalid.
+    pub unsafe fn try_with_position(needle: N, position: usize) -> Result<Self, SearcherError> {
+        SearcherError::check(&needle, position)?;
 
         let bytes = needle.as_bytes();
-        if let Some(size) = N::SIZE {
-            assert_eq!(size, bytes.len());
-        }
 
         let neon_hash = VectorHash::new(bytes[0], bytes[position]);
         let neon_half_hash = VectorHash::new(bytes[0], bytes[position]);
 
-        Self {
+        Ok(Self {
             position,
             neon_hash,
             neon_half_hash,
             needle,
-        }
+        })
     }
 
     #[inline]
-    unsafe fn neon_2_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn neon_2_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = VectorHash::<uint8x2_t>::from(&self.neon_half_hash);
-        crate::vector_search_in_neon_version(self.needle(), self.position(), haystack, end, &hash)
+        crate::vector_search_in_neon_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            &hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
-    unsafe fn neon_4_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn neon_4_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = VectorHash::<uint8x4_t>::from(&self.neon_half_hash);
-        crate::vector_search_in_neon_version(self.needle(), self.position(), haystack, end, &hash)
+        crate::vector_search_in_neon_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            &hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
-    unsafe fn neon_8_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn neon_8_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = &self.neon_half_hash;
-        crate::vector_search_in_neon_version(self.needle(), self.position(), haystack, end, hash)
+        crate::vector_search_in_neon_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
-    unsafe fn neon_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn neon_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = &self.neon_hash;
-        crate::vector_search_in_neon_version(self.needle(), self.position(), haystack, end, hash)
+        crate::vector_search_in_neon_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            hash,
+            verify,
+            f,
+        )
     }
 
-    /// Inlined version of `search_in` for hot call sites.
     #[inline]
-    pub unsafe fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+    unsafe fn search_filtered_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         if haystack.len() <= self.needle.size() {
-            return haystack == self.needle.as_bytes();
+            return crate::short_search_in(&self.needle, self.position, haystack, verify, f);
         }
 
         let end = haystack.len() - self.needle.size() + 1;
@@ -260,20 +333,60 @@ impl<N: Needle> NeonSearcher<N> {
         if end < uint8x2_t::LANES {
             unreachable!();
         } else if end < uint8x4_t::LANES {
-            self.neon_2_search_in(haystack, end)
+            self.neon_2_search_in(haystack, end, verify, f)
         } else if end < uint8x8_t::LANES {
-            self.neon_4_search_in(haystack, end)
+            self.neon_4_search_in(haystack, end, verify, f)
         } else if end < uint8x16_t::LANES {
-            self.neon_8_search_in(haystack, end)
+            self.neon_8_search_in(haystack, end, verify, f)
         } else {
-            self.neon_search_in(haystack, end)
+            self.neon_search_in(haystack, end, verify, f)
         }
     }
 
+    #[inline]
+    unsafe fn search_matches_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        self.search_filtered_in(haystack, true, f)
+    }
+
+    /// Inlined version of `search_in` for hot call sites.
+    #[inline]
+    pub unsafe fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+
     /// Performs a substring search for the `needle` within `haystack`.
     pub unsafe fn search_in(&self, haystack: &[u8]) -> bool {
         self.inlined_search_in(haystack)
     }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    pub unsafe fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        let size = self.needle.size();
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, size)
+        })
+    }
+
+    /// Calls `verify` with every candidate position of `haystack` at which
+    /// both the first byte of the needle and the byte at `position` match, in
+    /// increasing order, stopping as soon as `verify` returns `true`. Returns
+    /// whether any candidate was accepted.
+    ///
+    /// This exposes the SIMD filter used by `search_in` without the final
+    /// comparison with the needle, so that custom matchers can decide what
+    /// counts as a match. Every candidate leaves room for a full needle, i.e.
+    /// `candidate + needle.len() <= haystack.len()`.
+    pub unsafe fn search_candidates_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut verify: F,
+    ) -> bool {
+        self.search_filtered_in(haystack, false, &mut verify)
+    }
 }
 
 impl<N: Needle> Searcher<N> for NeonSearcher<N> {
@@ -288,18 +401,58 @@ impl<N: Needle> Searcher<N> for NeonSearcher<N> {
     }
 }
 
+// Constructing a `NeonSearcher` requires NEON support, which guarantees that
+// its methods can be safely called from the trait.
+impl<N: Needle> crate::SubstringSearch for NeonSearcher<N> {
+    fn needle(&self) -> &[u8] {
+        self.needle.as_bytes()
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        unsafe { self.inlined_search_in(haystack) }
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let mut found = None;
+        unsafe {
+            self.search_matches_in(haystack, &mut |position| {
+                found = Some(position);
+                true
+            })
+        };
+        found
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::NeonSearcher;
+    use crate::WordBoundary;
 
     impl crate::tests::TestSearcher for NeonSearcher<&[u8]> {
-        fn with_position(needle: &'static [u8], position: usize) -> NeonSearcher<&[u8]> {
+        fn with_position(needle: &'static [u8], position: usize) -> Self {
             unsafe { NeonSearcher::with_position(needle, position) }
         }
 
         fn search_in(&self, haystack: &[u8]) -> bool {
             unsafe { NeonSearcher::search_in(self, haystack) }
         }
+
+        fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+            unsafe { NeonSearcher::search_word_in(self, haystack, boundary) }
+        }
+
+        fn search_candidates_in(
+            &self,
+            haystack: &[u8],
+            verify: &mut dyn FnMut(usize) -> bool,
+        ) -> bool {
+            unsafe { NeonSearcher::search_candidates_in(self, haystack, verify) }
+        }
     }
 
     crate::generate_tests!(neon_searcher, NeonSearcher);
@@ -0,0 +1,301 @@
+use crate::SubstringSearch;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+/// Set of the indices of the haystacks of a batch which contain the needle,
+/// stored as 64-bit words, least significant bit first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// Creates a new set of `len` bits, all of them cleared.
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Returns the number of bits of the set, i.e. the number of haystacks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the set has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether bit `index` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn contains(&self, index: usize) -> bool {
+        assert!(index < self.len, "index out of bounds");
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Returns the number of bits set.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns an iterator over the indices of the bits set, in increasing
+    /// order.
+    pub fn iter(&self) -> Ones<'_> {
+        Ones {
+            words: self.words.iter(),
+            base: 0,
+            word: 0,
+        }
+    }
+
+    /// Returns the words of the set, bits beyond `len` being cleared.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns the words of the set, which can be passed to
+    /// `SubstringSearch::search_batch_in` to reuse the set across batches of
+    /// the same length. Any bit beyond `len` set through them is cleared when
+    /// the returned guard is dropped.
+    pub fn as_words_mut(&mut self) -> WordsMut<'_> {
+        WordsMut { set: self }
+    }
+
+    /// Returns the words of the set.
+    pub fn into_words(self) -> Vec<u64> {
+        self.words
+    }
+
+    /// Returns the set packed into `len.div_ceil(8)` bytes, least significant
+    /// bit first, as in Arrow validity bitmaps.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.words
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take(self.len.div_ceil(8))
+            .collect()
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Ones<'a>;
+
+    fn into_iter(self) -> Ones<'a> {
+        self.iter()
+    }
+}
+
+/// Mutable access to the words of a `BitSet`, returned by
+/// `BitSet::as_words_mut`.
+pub struct WordsMut<'a> {
+    set: &'a mut BitSet,
+}
+
+impl Deref for WordsMut<'_> {
+    type Target = [u64];
+
+    fn deref(&self) -> &[u64] {
+        &self.set.words
+    }
+}
+
+impl DerefMut for WordsMut<'_> {
+    fn deref_mut(&mut self) -> &mut [u64] {
+        &mut self.set.words
+    }
+}
+
+impl Drop for WordsMut<'_> {
+    fn drop(&mut self) {
+        // Bits beyond `len` would otherwise be counted and iterated over.
+        let bits = self.set.len % 64;
+        if let (Some(last), true) = (self.set.words.last_mut(), bits != 0) {
+            *last &= (1 << bits) - 1;
+        }
+    }
+}
+
+/// Iterator over the indices of the bits set in a `BitSet`.
+pub struct Ones<'a> {
+    words: core::slice::Iter<'a, u64>,
+    base: usize,
+    word: u64,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            self.word = *self.words.next()?;
+            self.base += 64;
+        }
+
+        let index = self.base - 64 + self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(index)
+    }
+}
+
+/// Returns the set of the indices of `haystacks` which contain the needle of
+/// `searcher`.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::{batch::search_batch, memmem::Finder};
+///
+/// let haystacks: [&[u8]; 4] = [b"foo", b"bar", b"foobar", b""];
+/// let matches = search_batch(&Finder::new("bar"), &haystacks);
+///
+/// assert!(matches.iter().eq([1, 2]));
+/// ```
+pub fn search_batch<S: ?Sized + SubstringSearch>(searcher: &S, haystacks: &[&[u8]]) -> BitSet {
+    let mut matches = BitSet::new(haystacks.len());
+    searcher.search_batch_in(haystacks, &mut matches.as_words_mut());
+    matches
+}
+
+/// Same as `search_batch` but for haystacks stored contiguously in `data`,
+/// where haystack `i` is `data[offsets[i]..offsets[i + 1]]`, as in Arrow
+/// binary arrays. An empty `offsets` contains no haystack.
+///
+/// # Panics
+///
+/// Panics if `offsets` is decreasing or out of bounds for `data`.
+pub fn search_batch_offsets<S: ?Sized + SubstringSearch>(
+    searcher: &S,
+    offsets: &[usize],
+    data: &[u8],
+) -> BitSet {
+    let mut matches = BitSet::new(offsets.len().saturating_sub(1));
+    searcher.search_offsets_in(offsets, data, &mut matches.as_words_mut());
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{search_batch, search_batch_offsets, BitSet};
+    use crate::builder::{Backend, SearcherBuilder};
+    use crate::memmem::Finder;
+    use crate::SubstringSearch;
+
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    /// Checks the batch searches of `searcher` against searching each of
+    /// `haystacks` separately.
+    fn search<S: ?Sized + SubstringSearch>(searcher: &S, haystacks: &[&[u8]]) {
+        let needle = searcher.needle();
+        let expected: Vec<usize> = (0..haystacks.len())
+            .filter(|&i| memchr::memmem::find(haystacks[i], needle).is_some())
+            .collect();
+
+        let matches = search_batch(searcher, haystacks);
+        assert_eq!(matches.len(), haystacks.len());
+        assert!(matches.iter().eq(expected.iter().copied()), "{needle:?}");
+        assert_eq!(matches.count_ones(), expected.len());
+
+        let mut offsets = vec![0];
+        for haystack in haystacks {
+            offsets.push(offsets.last().unwrap() + haystack.len());
+        }
+        let data = haystacks.concat();
+        assert_eq!(search_batch_offsets(searcher, &offsets, &data), matches);
+    }
+
+    fn search_all(haystacks: &[&[u8]], needles: &[&[u8]]) {
+        for &needle in needles {
+            search(&Finder::new(needle), haystacks);
+            if needle.is_empty() {
+                continue;
+            }
+
+            for backend in [Backend::Auto, Backend::Scalar] {
+                let searcher = SearcherBuilder::new().backend(backend).build(needle);
+                search(&searcher.unwrap(), haystacks);
+            }
+
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            if is_x86_feature_detected!("avx2") {
+                let searcher = unsafe { crate::x86::Avx2Searcher::new(needle) };
+                search(&searcher, haystacks);
+            }
+        }
+    }
+
+    #[test]
+    fn batch_search() {
+        // Haystacks of every length up to twice the width of AVX2 vectors.
+        let haystack = b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxabcxxxxxxxxxxxxxxxxxxxxxxxxxxabc";
+        let haystacks: Vec<&[u8]> = (0..=haystack.len())
+            .flat_map(|end| (0..=end).map(move |start| &haystack[start..end]))
+            .collect();
+        let needles: [&[u8]; 6] = [b"", b"a", b"ab", b"abc", b"xabcx", b"xxxxxxxxxxxxxxxxa"];
+
+        search_all(&haystacks, &needles);
+        search_all(&[], &needles);
+    }
+
+    #[test]
+    fn batch_search_words() {
+        let haystacks: Vec<&[u8]> = WORDS.split(|&b| b == b'\n').collect();
+        let needles: [&[u8]; 5] = [b"a", b"ab", b"ation", b"ing", b"zzz"];
+
+        search_all(&haystacks, &needles);
+    }
+
+    #[test]
+    fn batch_search_clears_matches() {
+        let haystacks: [&[u8]; 3] = [b"abc", b"xyz", b"abc"];
+        let mut matches = [u64::MAX; 2];
+
+        Finder::new("abc").search_batch_in(&haystacks, &mut matches);
+        assert_eq!(matches, [0b101, 0]);
+
+        Finder::new("abc").search_offsets_in(&[], b"", &mut matches);
+        assert_eq!(matches, [0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn batch_search_too_few_matches() {
+        let haystacks = [&b"abc"[..]; 65];
+        Finder::new("abc").search_batch_in(&haystacks, &mut [0]);
+    }
+
+    #[test]
+    fn bit_set() {
+        let mut set = BitSet::new(130);
+        assert_eq!(set.as_words().len(), 3);
+        assert_eq!(set.count_ones(), 0);
+
+        set.as_words_mut()[0] = 1 << 3;
+        set.as_words_mut()[2] = 1 << 1;
+        assert!(set.contains(3) && set.contains(129) && !set.contains(4));
+        assert!(set.iter().eq([3, 129]));
+        assert_eq!(set.to_bytes().len(), 17);
+        assert_eq!(set.to_bytes()[..2], [8, 0]);
+        assert_eq!(set.to_bytes()[16], 2);
+        assert_eq!(set.as_words(), [8, 0, 2]);
+
+        // Bits beyond the length are cleared once the words are released.
+        set.as_words_mut()[2] = u64::MAX;
+        assert_eq!(set.count_ones(), 3);
+        assert!(set.iter().eq([3, 128, 129]));
+        assert_eq!(set.into_words(), [8, 0, 3]);
+
+        assert!(BitSet::new(0).is_empty());
+        assert!(BitSet::default().iter().next().is_none());
+    }
+}
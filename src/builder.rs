@@ -0,0 +1,728 @@
+use crate::{MemchrSearcher, Needle, SearcherError, SubstringSearch, WordBoundary};
+use alloc::{boxed::Box, vec::Vec};
+
+/// Size of the blocks of haystack lowercased at a time by ASCII case
+/// insensitive searchers.
+const LOWERCASE_BLOCK: usize = 4096;
+
+/// Number of needle bytes which may be compared when verifying candidates, per
+/// byte of haystack, before a searcher with an adversarial fallback switches to
+/// the scalar backend.
+const VERIFY_FACTOR: usize = 4;
+
+/// Strategy used to choose the needle byte compared alongside the first one to
+/// filter candidate positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Probe {
+    /// Uses the last byte of the needle, as done by `new`.
+    #[default]
+    Last,
+    /// Uses the byte at the given index, as done by `with_position`.
+    Position(usize),
+    /// Uses the last byte of the needle which differs from the first one, so
+    /// that needles such as `abca` filter on two different bytes.
+    DistinctFromFirst,
+}
+
+impl Probe {
+    fn position(self, needle: &[u8]) -> usize {
+        match self {
+            // Wrapping prevents panicking on unsigned integer underflow when
+            // `needle` is empty.
+            Self::Last => needle.len().wrapping_sub(1),
+            Self::Position(position) => position,
+            Self::DistinctFromFirst => needle
+                .iter()
+                .rposition(|&byte| byte != needle[0])
+                .unwrap_or(needle.len().wrapping_sub(1)),
+        }
+    }
+}
+
+/// Returns whether AVX2 is available, detected at runtime when `std` is
+/// enabled or at compile time otherwise.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn has_avx2() -> bool {
+    #[cfg(feature = "std")]
+    return is_x86_feature_detected!("avx2");
+    #[cfg(not(feature = "std"))]
+    return cfg!(target_feature = "avx2");
+}
+
+/// Returns whether NEON is available, detected at runtime when `std` is
+/// enabled or at compile time otherwise.
+#[cfg(target_arch = "aarch64")]
+fn has_neon() -> bool {
+    #[cfg(feature = "std")]
+    return std::arch::is_aarch64_feature_detected!("neon");
+    #[cfg(not(feature = "std"))]
+    return cfg!(target_feature = "neon");
+}
+
+/// Searcher implementation used by a `ConfiguredSearcher`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Selects the fastest backend available at runtime.
+    #[default]
+    Auto,
+    /// `DynamicAvx2Searcher`, available on x86 processors supporting AVX2.
+    Avx2,
+    /// `NeonSearcher`, available on aarch64 processors supporting NEON.
+    Neon,
+    /// `Wasm32Searcher`, available when compiling for wasm32 with SIMD128.
+    Wasm32,
+    /// `StdSimdSearcher`, available with the `stdsimd` feature.
+    StdSimd,
+    /// Scalar search using `memchr`, available everywhere.
+    Scalar,
+}
+
+impl Backend {
+    /// Returns the fastest backend available at runtime, or at compile time
+    /// without the `std` feature.
+    #[allow(unreachable_code)]
+    pub fn detect() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if has_avx2() {
+            return Self::Avx2;
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        if has_neon() {
+            return Self::Neon;
+        }
+
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        return Self::Wasm32;
+
+        #[cfg(feature = "stdsimd")]
+        return Self::StdSimd;
+
+        Self::Scalar
+    }
+}
+
+/// Match semantics of a `ConfiguredSearcher`.
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::large_enum_variant)]
+pub enum MatchSemantics {
+    /// Reports every occurrence of the needle.
+    #[default]
+    Substring,
+    /// Only reports occurrences delimited by the given word boundary, as done
+    /// by `search_word_in`.
+    Word(WordBoundary),
+}
+
+/// Searcher which can report the candidate positions passing its filter, so
+/// that a `ConfiguredSearcher` can bound the time spent verifying them.
+pub(crate) trait CandidateSearch: SubstringSearch {
+    /// Same as the `search_candidates_in` method of the searchers.
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool;
+}
+
+/// Calls `verify` with every match of `searcher` within `haystack`, for
+/// searchers which do not filter candidates.
+fn search_matches_as_candidates<S: SubstringSearch + ?Sized>(
+    searcher: &S,
+    haystack: &[u8],
+    verify: &mut dyn FnMut(usize) -> bool,
+) -> bool {
+    let mut start = 0;
+    while let Some(i) = searcher.find(&haystack[start..]) {
+        if verify(start + i) {
+            return true;
+        }
+        start += i + 1;
+    }
+    false
+}
+
+impl CandidateSearch for MemchrSearcher {
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool {
+        search_matches_as_candidates(self, haystack, verify)
+    }
+}
+
+// Constructing the architecture specific searchers checks the availability of
+// their target features, so calling them is safe.
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl<N: Needle> CandidateSearch for crate::x86::DynamicAvx2Searcher<N> {
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool {
+        unsafe { crate::x86::DynamicAvx2Searcher::search_candidates_in(self, haystack, verify) }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl<N: Needle> CandidateSearch for crate::aarch64::NeonSearcher<N> {
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool {
+        unsafe { crate::aarch64::NeonSearcher::search_candidates_in(self, haystack, verify) }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+impl<N: Needle> CandidateSearch for crate::wasm32::Wasm32Searcher<N> {
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool {
+        unsafe { crate::wasm32::Wasm32Searcher::search_candidates_in(self, haystack, verify) }
+    }
+}
+
+#[cfg(feature = "stdsimd")]
+impl<N: Needle> CandidateSearch for crate::stdsimd::StdSimdSearcher<N> {
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool {
+        crate::stdsimd::StdSimdSearcher::search_candidates_in(self, haystack, verify)
+    }
+}
+
+/// Fallback searcher for needles which should not be searched using SIMD.
+struct ScalarSearcher(memchr::memmem::Finder<'static>);
+
+impl SubstringSearch for ScalarSearcher {
+    fn needle(&self) -> &[u8] {
+        self.0.needle()
+    }
+
+    fn position(&self) -> usize {
+        0
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        self.0.find(haystack).is_some()
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.0.find(haystack)
+    }
+}
+
+impl CandidateSearch for ScalarSearcher {
+    fn search_candidates_in(&self, haystack: &[u8], verify: &mut dyn FnMut(usize) -> bool) -> bool {
+        search_matches_as_candidates(self, haystack, verify)
+    }
+}
+
+/// Creates a searcher for `needle` using `backend`, which must not be `Auto`.
+pub(crate) fn backend_searcher<'n, N: Needle + Send + Sync + 'n>(
+    backend: Backend,
+    needle: N,
+    position: usize,
+) -> Result<Box<dyn CandidateSearch + Send + Sync + 'n>, SearcherError> {
+    SearcherError::check(&needle, position)?;
+
+    // Constructing the architecture specific searchers is safe since the
+    // required target features have been checked.
+    match backend {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Backend::Avx2 if has_avx2() => Ok(Box::new(unsafe {
+            crate::x86::DynamicAvx2Searcher::try_with_position(needle, position)?
+        })),
+        #[cfg(target_arch = "aarch64")]
+        Backend::Neon if has_neon() => Ok(Box::new(unsafe {
+            crate::aarch64::NeonSearcher::try_with_position(needle, position)?
+        })),
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        Backend::Wasm32 => Ok(Box::new(unsafe {
+            crate::wasm32::Wasm32Searcher::try_with_position(needle, position)?
+        })),
+        #[cfg(feature = "stdsimd")]
+        Backend::StdSimd => Ok(Box::new(
+            crate::stdsimd::StdSimdSearcher::try_with_position(needle, position)?,
+        )),
+        Backend::Scalar => Ok(match *needle.as_bytes() {
+            [byte] => Box::new(MemchrSearcher::new(byte)),
+            ref bytes => Box::new(ScalarSearcher(
+                memchr::memmem::Finder::new(bytes).into_owned(),
+            )),
+        }),
+        _ => Err(SearcherError::UnsupportedBackend(backend)),
+    }
+}
+
+/// Creates a searcher for the non-empty `needle` using the fastest backend
+/// available at runtime.
+pub(crate) fn candidate_searcher<'n, N: Needle + Send + Sync + 'n>(
+    needle: N,
+) -> Box<dyn CandidateSearch + Send + Sync + 'n> {
+    match *needle.as_bytes() {
+        [byte] => Box::new(MemchrSearcher::new(byte)),
+        ref bytes => {
+            let position = bytes.len() - 1;
+            // The detected backend is always available and the needle is valid.
+            backend_searcher(Backend::detect(), needle, position).unwrap()
+        }
+    }
+}
+
+/// Builder collecting the settings of a searcher, which are validated when
+/// calling `build`.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::builder::{Backend, MatchSemantics, Probe, SearcherBuilder};
+/// use sliceslice::{SubstringSearch, WordBoundary};
+///
+/// let searcher = SearcherBuilder::new()
+///     .probe(Probe::DistinctFromFirst)
+///     .backend(Backend::Auto)
+///     .semantics(MatchSemantics::Word(WordBoundary::ascii()))
+///     .ascii_case_insensitive(true)
+///     .build(&b"select"[..])
+///     .unwrap();
+///
+/// assert_eq!(searcher.find(b"SELECTION; SELECT *"), Some(11));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SearcherBuilder {
+    probe: Probe,
+    backend: Backend,
+    semantics: MatchSemantics,
+    ascii_case_insensitive: bool,
+    adversarial_fallback: bool,
+    min_simd_len: usize,
+    max_simd_len: Option<usize>,
+}
+
+impl Default for SearcherBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearcherBuilder {
+    /// Creates a new builder with the default settings, which select the
+    /// fastest backend available at runtime and filter on the last byte of
+    /// the needle.
+    pub fn new() -> Self {
+        Self {
+            probe: Probe::Last,
+            backend: Backend::Auto,
+            semantics: MatchSemantics::Substring,
+            ascii_case_insensitive: false,
+            adversarial_fallback: false,
+            min_simd_len: 2,
+            max_simd_len: None,
+        }
+    }
+
+    /// Sets the strategy used to choose the `position` of the searcher.
+    pub fn probe(&mut self, probe: Probe) -> &mut Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Forces the use of `backend`, or selects one at runtime for
+    /// `Backend::Auto`.
+    pub fn backend(&mut self, backend: Backend) -> &mut Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Sets the match semantics.
+    pub fn semantics(&mut self, semantics: MatchSemantics) -> &mut Self {
+        self.semantics = semantics;
+        self
+    }
+
+    /// Sets whether ASCII letters match regardless of their case. The needle is
+    /// then stored in lowercase, and haystacks are lowercased one block at a
+    /// time before being searched.
+    pub fn ascii_case_insensitive(&mut self, yes: bool) -> &mut Self {
+        self.ascii_case_insensitive = yes;
+        self
+    }
+
+    /// Sets whether searches switch to the scalar backend once verifying the
+    /// candidate positions passing the SIMD filter has compared more needle
+    /// bytes than a small multiple of the haystack length. The running time
+    /// is then linear even for adversarial haystacks, where most positions are
+    /// candidates but not matches. Disabled by default.
+    pub fn adversarial_fallback(&mut self, yes: bool) -> &mut Self {
+        self.adversarial_fallback = yes;
+        self
+    }
+
+    /// Sets the minimum needle length searched using SIMD when the backend is
+    /// `Backend::Auto`. Shorter needles use the scalar backend. Defaults to 2.
+    pub fn min_simd_len(&mut self, len: usize) -> &mut Self {
+        self.min_simd_len = len;
+        self
+    }
+
+    /// Sets the maximum needle length searched using SIMD when the backend is
+    /// `Backend::Auto`. Longer needles use the scalar backend, whose running
+    /// time is linear even for adversarial haystacks. Unlimited by default.
+    pub fn max_simd_len(&mut self, len: Option<usize>) -> &mut Self {
+        self.max_simd_len = len;
+        self
+    }
+
+    /// Builds a searcher for `needle` with the configured settings.
+    ///
+    /// Returns an error if `needle` is empty, if the probe position is not a
+    /// valid index for `needle`, if the associated `SIZE` constant does not
+    /// correspond to the actual size of `needle`, if the SIMD length thresholds
+    /// are inconsistent or if the forced backend is not available.
+    pub fn build<'n, N: Needle + Send + Sync + 'n>(
+        &self,
+        needle: N,
+    ) -> Result<ConfiguredSearcher<'n>, SearcherError> {
+        let (min, max) = (self.min_simd_len, self.max_simd_len);
+        if min == 0 || max.is_some_and(|max| max < min) {
+            return Err(SearcherError::InvalidThresholds { min, max });
+        }
+
+        let bytes = needle.as_bytes();
+        let size = bytes.len();
+        let position = self.probe.position(bytes);
+
+        let backend = match self.backend {
+            Backend::Auto if size < min || max.is_some_and(|max| size > max) => Backend::Scalar,
+            Backend::Auto => Backend::detect(),
+            backend => backend,
+        };
+
+        let boundary = match &self.semantics {
+            MatchSemantics::Substring => None,
+            MatchSemantics::Word(boundary) => Some(boundary.clone()),
+        };
+
+        // The needle is copied after checking its `SIZE` constant, since the
+        // architecture specific searchers rely on `as_bytes` always returning
+        // the same bytes, which arbitrary `Needle` implementations may not.
+        SearcherError::check(&needle, position)?;
+        let mut needle: Box<[u8]> = bytes.into();
+        if self.ascii_case_insensitive {
+            needle.make_ascii_lowercase();
+        }
+        let searcher = backend_searcher(backend, needle, position)?;
+
+        // The scalar backend is already linear.
+        let fallback = (self.adversarial_fallback && backend != Backend::Scalar)
+            .then(|| memchr::memmem::Finder::new(searcher.needle()).into_owned());
+
+        Ok(ConfiguredSearcher {
+            searcher,
+            backend,
+            boundary,
+            ascii_case_insensitive: self.ascii_case_insensitive,
+            fallback,
+        })
+    }
+}
+
+/// Searcher configured using a `SearcherBuilder`. It can safely be used on any
+/// processor since the availability of its backend has been checked when
+/// building it.
+pub struct ConfiguredSearcher<'n> {
+    searcher: Box<dyn CandidateSearch + Send + Sync + 'n>,
+    backend: Backend,
+    boundary: Option<WordBoundary>,
+    ascii_case_insensitive: bool,
+    fallback: Option<memchr::memmem::Finder<'static>>,
+}
+
+impl ConfiguredSearcher<'_> {
+    /// Returns the backend used, which is never `Backend::Auto`.
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Returns whether the underlying searcher can be used directly, without
+    /// checking word boundaries, lowercasing haystacks or bounding the time
+    /// spent verifying candidates.
+    fn is_plain(&self) -> bool {
+        self.boundary.is_none() && !self.ascii_case_insensitive && self.fallback.is_none()
+    }
+
+    /// Returns the position of the leftmost occurrence of the needle within
+    /// `haystack` using the underlying searcher, switching to the scalar
+    /// fallback, if any, once too many candidates have been rejected.
+    fn find_guarded(&self, haystack: &[u8]) -> Option<usize> {
+        let Some(fallback) = &self.fallback else {
+            return self.searcher.find(haystack);
+        };
+
+        let needle = self.needle();
+        let mut budget = VERIFY_FACTOR * haystack.len();
+        let mut found = None;
+        let mut rejected = None;
+        self.searcher
+            .search_candidates_in(haystack, &mut |candidate| {
+                if haystack[candidate..].starts_with(needle) {
+                    found = Some(candidate);
+                } else if budget >= needle.len() {
+                    budget -= needle.len();
+                    return false;
+                } else {
+                    rejected = Some(candidate);
+                }
+                true
+            });
+
+        // Candidates are verified in increasing order, so there is no match
+        // before the one which exhausted the budget.
+        match rejected {
+            Some(start) => fallback.find(&haystack[start..]).map(|i| start + i),
+            None => found,
+        }
+    }
+
+    /// Returns the position of the leftmost occurrence of the needle within
+    /// `haystack`, ignoring word boundaries.
+    fn find_substring(&self, haystack: &[u8]) -> Option<usize> {
+        if !self.ascii_case_insensitive {
+            return self.find_guarded(haystack);
+        }
+
+        // Each block is searched along with the start of the next one, so
+        // that occurrences starting within it are found in full.
+        let size = self.needle().len();
+        let mut lowercase = Vec::with_capacity(LOWERCASE_BLOCK + size - 1);
+        let mut start = 0;
+        while start + size <= haystack.len() {
+            let end = haystack.len().min(start + LOWERCASE_BLOCK + size - 1);
+            lowercase.clear();
+            lowercase.extend(haystack[start..end].iter().map(u8::to_ascii_lowercase));
+            if let Some(i) = self.find_guarded(&lowercase) {
+                return Some(start + i);
+            }
+            start += LOWERCASE_BLOCK;
+        }
+        None
+    }
+}
+
+impl SubstringSearch for ConfiguredSearcher<'_> {
+    fn needle(&self) -> &[u8] {
+        self.searcher.needle()
+    }
+
+    fn position(&self) -> usize {
+        self.searcher.position()
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        if self.is_plain() {
+            self.searcher.search_in(haystack)
+        } else {
+            self.find(haystack).is_some()
+        }
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let boundary = match &self.boundary {
+            None => return self.find_substring(haystack),
+            Some(boundary) => boundary,
+        };
+
+        let size = self.needle().len();
+        let mut start = 0;
+        while start < haystack.len() {
+            let position = start + self.find_substring(&haystack[start..])?;
+            if boundary.is_match(haystack, position, size) {
+                return Some(position);
+            }
+            start = position + 1;
+        }
+        None
+    }
+
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        if self.is_plain() {
+            self.searcher.search_batch_in(haystacks, matches)
+        } else {
+            crate::batch_search_in(haystacks.iter().copied(), matches, |haystack| {
+                self.search_in(haystack)
+            })
+        }
+    }
+
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        if self.is_plain() {
+            self.searcher.search_offsets_in(offsets, data, matches)
+        } else {
+            crate::batch_search_in(
+                crate::offsets_haystacks(offsets, data),
+                matches,
+                |haystack| self.search_in(haystack),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, MatchSemantics, Probe, SearcherBuilder};
+    use crate::{SearcherError, SubstringSearch, WordBoundary};
+
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    fn search(builder: &SearcherBuilder) {
+        for needle in [&b"a"[..], b"ing", b"ation\n", b"aardvark", b"zzz"] {
+            let searcher = builder.build(needle).unwrap();
+            assert_ne!(searcher.backend(), Backend::Auto);
+            assert_eq!(
+                searcher.find(WORDS),
+                memchr::memmem::find(WORDS, needle),
+                "{:?}",
+                needle
+            );
+        }
+    }
+
+    #[test]
+    fn builder_search() {
+        search(&SearcherBuilder::new());
+        search(SearcherBuilder::new().backend(Backend::Scalar));
+        search(SearcherBuilder::new().backend(Backend::detect()));
+        search(SearcherBuilder::new().probe(Probe::Position(0)));
+        search(SearcherBuilder::new().probe(Probe::DistinctFromFirst));
+        search(SearcherBuilder::new().min_simd_len(4).max_simd_len(Some(6)));
+        search(SearcherBuilder::new().adversarial_fallback(true));
+    }
+
+    #[test]
+    fn builder_adversarial_fallback() {
+        // Every position is a candidate, but only the last one is a match.
+        let needle = [&b"a".repeat(32)[..], b"ba"].concat();
+        let haystack = [&b"a".repeat(10000)[..], b"ba"].concat();
+
+        for ascii_case_insensitive in [false, true] {
+            let searcher = SearcherBuilder::new()
+                .adversarial_fallback(true)
+                .ascii_case_insensitive(ascii_case_insensitive)
+                .build(&needle[..])
+                .unwrap();
+
+            assert_eq!(searcher.find(&haystack), Some(10000 - 32));
+            assert_eq!(searcher.find(&haystack[..10001]), None);
+            assert_eq!(searcher.find(&haystack[9000..]), Some(1000 - 32));
+            assert_eq!(searcher.find(&needle), Some(0));
+            assert!(searcher.search_in(&haystack));
+        }
+    }
+
+    #[test]
+    fn builder_thresholds() {
+        let mut builder = SearcherBuilder::new();
+        builder.min_simd_len(4).max_simd_len(Some(6));
+
+        let backend = |needle: &'static [u8]| builder.build(needle).unwrap().backend();
+        assert_eq!(backend(b"foo"), Backend::Scalar);
+        assert_eq!(backend(b"fooba"), Backend::detect());
+        assert_eq!(backend(b"foobarbaz"), Backend::Scalar);
+    }
+
+    #[test]
+    fn builder_probe() {
+        let position = |probe| {
+            let searcher = SearcherBuilder::new()
+                .backend(Backend::detect())
+                .probe(probe)
+                .build(&b"abcaa"[..])
+                .unwrap();
+            SubstringSearch::position(&searcher)
+        };
+
+        if Backend::detect() != Backend::Scalar {
+            assert_eq!(position(Probe::Last), 4);
+            assert_eq!(position(Probe::Position(1)), 1);
+            assert_eq!(position(Probe::DistinctFromFirst), 2);
+        }
+    }
+
+    #[test]
+    fn builder_word_semantics() {
+        let searcher = SearcherBuilder::new()
+            .semantics(MatchSemantics::Word(WordBoundary::ascii()))
+            .build(&b"select"[..])
+            .unwrap();
+
+        assert_eq!(searcher.find(b"select * from"), Some(0));
+        assert_eq!(searcher.find(b"selection; select *"), Some(11));
+        assert_eq!(searcher.find(b"selection; selected"), None);
+        assert!(!searcher.search_in(b"preselect"));
+    }
+
+    #[test]
+    fn builder_case_insensitive() {
+        let uppercase = WORDS.to_ascii_uppercase();
+
+        for backend in [Backend::Auto, Backend::Scalar, Backend::detect()] {
+            for needle in [&b"a"[..], b"Ing", b"ATION\n", b"aardVARK", b"ZZZ"] {
+                let searcher = SearcherBuilder::new()
+                    .backend(backend)
+                    .ascii_case_insensitive(true)
+                    .build(needle)
+                    .unwrap();
+                assert_eq!(searcher.needle(), needle.to_ascii_lowercase());
+
+                let expected = memchr::memmem::find(WORDS, &needle.to_ascii_lowercase());
+                assert_eq!(searcher.find(WORDS), expected, "{:?}", needle);
+                assert_eq!(searcher.find(&uppercase), expected, "{:?}", needle);
+                assert_eq!(searcher.search_in(&uppercase), expected.is_some());
+            }
+        }
+
+        // Occurrences straddling the blocks lowercased at a time.
+        let searcher = SearcherBuilder::new()
+            .ascii_case_insensitive(true)
+            .build(&b"abcd"[..])
+            .unwrap();
+        for position in 4090..4100 {
+            let mut haystack = vec![b'x'; 8200];
+            haystack[position..position + 4].copy_from_slice(b"AbCd");
+            assert_eq!(searcher.find(&haystack), Some(position));
+            assert_eq!(searcher.find(&haystack[..position + 3]), None);
+        }
+
+        let searcher = SearcherBuilder::new()
+            .semantics(MatchSemantics::Word(WordBoundary::ascii()))
+            .ascii_case_insensitive(true)
+            .build(&b"Select"[..])
+            .unwrap();
+
+        assert_eq!(searcher.find(b"SELECT * FROM"), Some(0));
+        assert_eq!(searcher.find(b"Selection; sElEcT *"), Some(11));
+        assert!(!searcher.search_in(b"PRESELECT"));
+    }
+
+    #[test]
+    fn builder_errors() {
+        let error = |builder: &SearcherBuilder, needle: &'static [u8]| builder.build(needle).err();
+
+        assert_eq!(
+            error(&SearcherBuilder::new(), b""),
+            Some(SearcherError::EmptyNeedle)
+        );
+        assert_eq!(
+            error(SearcherBuilder::new().probe(Probe::Position(3)), b"foo"),
+            Some(SearcherError::PositionOutOfRange {
+                position: 3,
+                size: 3
+            })
+        );
+        assert_eq!(
+            error(SearcherBuilder::new().min_simd_len(0), b"foo"),
+            Some(SearcherError::InvalidThresholds { min: 0, max: None })
+        );
+        assert_eq!(
+            error(
+                SearcherBuilder::new().min_simd_len(4).max_simd_len(Some(3)),
+                b"foo"
+            ),
+            Some(SearcherError::InvalidThresholds {
+                min: 4,
+                max: Some(3)
+            })
+        );
+
+        #[cfg(not(feature = "stdsimd"))]
+        assert_eq!(
+            error(SearcherBuilder::new().backend(Backend::StdSimd), b"foo"),
+            Some(SearcherError::UnsupportedBackend(Backend::StdSimd))
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use crate::batch::BitSet;
+use crate::like::{LikeError, LikeMatcher};
+use crate::SubstringSearch;
+
+/// Type of the offsets delimiting the strings of a `StringArray`, such as the
+/// `i32` offsets of Arrow `Utf8` arrays and the `i64` offsets of `LargeUtf8`
+/// arrays.
+pub trait Offset: Copy {
+    /// Converts the offset into an index into the values buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is negative or does not fit in a `usize`.
+    fn to_index(self) -> usize;
+}
+
+macro_rules! offset {
+    ($($T:ty),*) => {$(
+        impl Offset for $T {
+            #[inline]
+            fn to_index(self) -> usize {
+                usize::try_from(self).expect("invalid offset")
+            }
+        }
+    )*};
+}
+
+offset!(i32, i64);
+
+/// Borrowed array of strings in the Arrow layout, where string `i` is
+/// `values[offsets[i]..offsets[i + 1]]`, with an optional validity bitmap.
+///
+/// Predicates are evaluated for every string into a `BitSet`, whose bit `i` is
+/// set if string `i` is valid and satisfies the predicate. The bitmap can be
+/// converted into the Arrow layout using `BitSet::to_bytes`.
+#[derive(Clone, Copy, Debug)]
+pub struct StringArray<'a, O: Offset> {
+    offsets: &'a [O],
+    values: &'a [u8],
+    validity: Option<&'a [u8]>,
+}
+
+impl<'a, O: Offset> StringArray<'a, O> {
+    /// Creates a new array of the strings of `values` delimited by `offsets`.
+    /// An empty `offsets` contains no string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offsets` is negative, decreasing or out of bounds for
+    /// `values`.
+    pub fn new(offsets: &'a [O], values: &'a [u8]) -> Self {
+        let mut previous = 0;
+        for (i, offset) in offsets.iter().enumerate() {
+            let offset = offset.to_index();
+            assert!(i == 0 || previous <= offset, "decreasing offsets");
+            previous = offset;
+        }
+        assert!(previous <= values.len(), "offsets out of bounds");
+
+        Self {
+            offsets,
+            values,
+            validity: None,
+        }
+    }
+
+    /// Sets the validity bitmap of the array, where string `i` is null if bit
+    /// `i % 8` of `validity[i / 8]` is cleared. Null strings never satisfy
+    /// predicates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `validity` has fewer bits than there are strings.
+    pub fn with_validity(mut self, validity: &'a [u8]) -> Self {
+        assert!(
+            validity.len() * 8 >= self.len(),
+            "validity bitmap too short"
+        );
+        self.validity = Some(validity);
+        self
+    }
+
+    /// Returns the number of strings of the array.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Returns whether the array contains no string.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns string `index` of the array, regardless of its validity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn value(&self, index: usize) -> &'a [u8] {
+        let (start, end) = self.bounds(index);
+        &self.values[start..end]
+    }
+
+    /// Returns the bounds of string `index` within the values buffer.
+    #[inline]
+    fn bounds(&self, index: usize) -> (usize, usize) {
+        let start = self.offsets[index].to_index();
+        let end = self.offsets[index + 1].to_index();
+        (start, end)
+    }
+
+    /// Returns whether each string contains the needle of `searcher`.
+    ///
+    /// The values buffer is searched in a single pass, mapping each match back
+    /// to its string, then resuming the search at the start of the next one.
+    /// Matches straddling two strings are discarded, since the leftmost match
+    /// starting within a string is its only candidate.
+    pub fn contains<S: ?Sized + SubstringSearch>(&self, searcher: &S) -> BitSet {
+        let size = searcher.needle().len();
+        if size == 0 {
+            return self.select(|_| true);
+        }
+
+        let mut matches = BitSet::new(self.len());
+        if let Some(&last) = self.offsets.last() {
+            let mut words = matches.as_words_mut();
+            let end = last.to_index();
+            let mut start = self.offsets[0].to_index();
+            let mut row = 0;
+
+            while let Some(i) = searcher.find(&self.values[start..end]) {
+                let position = start + i;
+                while self.offsets[row + 1].to_index() <= position {
+                    row += 1;
+                }
+
+                // Any later match starting within this string would straddle
+                // its end as well, so the search resumes at the next one.
+                let row_end = self.offsets[row + 1].to_index();
+                if position + size <= row_end {
+                    words[row / 64] |= 1 << (row % 64);
+                }
+                start = row_end;
+                row += 1;
+            }
+        }
+        self.mask(matches)
+    }
+
+    /// Returns whether each string starts with `prefix`.
+    ///
+    /// Unlike `contains`, this does not scan the values buffer but compares
+    /// the start of each string with `prefix` using a scalar comparison, which
+    /// reads at most `prefix.len()` bytes per string.
+    pub fn starts_with(&self, prefix: &[u8]) -> BitSet {
+        self.select(|value| value.starts_with(prefix))
+    }
+
+    /// Returns whether each string ends with `suffix`.
+    ///
+    /// As with `starts_with`, the end of each string is compared with `suffix`
+    /// using a scalar comparison.
+    pub fn ends_with(&self, suffix: &[u8]) -> BitSet {
+        self.select(|value| value.ends_with(suffix))
+    }
+
+    /// Returns whether each string matches the SQL `LIKE` `pattern`, escaped
+    /// with a backslash.
+    pub fn like(&self, pattern: &[u8]) -> Result<BitSet, LikeError> {
+        Ok(self.like_matches(&LikeMatcher::new(pattern)?))
+    }
+
+    /// Returns whether each string matches the pattern of `matcher`. Patterns
+    /// of the form `%literal%` are evaluated in a single pass as `contains`.
+    pub fn like_matches(&self, matcher: &LikeMatcher) -> BitSet {
+        match matcher.as_contains() {
+            Some(finder) => self.contains(finder),
+            None => self.select(|value| matcher.is_match(value)),
+        }
+    }
+
+    /// Returns whether each string satisfies `predicate`.
+    fn select<F: FnMut(&[u8]) -> bool>(&self, predicate: F) -> BitSet {
+        let mut matches = BitSet::new(self.len());
+        let values = (0..self.len()).map(|index| self.value(index));
+        crate::batch_search_in(values, &mut matches.as_words_mut(), predicate);
+        self.mask(matches)
+    }
+
+    /// Clears the bits of the null strings.
+    fn mask(&self, mut matches: BitSet) -> BitSet {
+        if let Some(validity) = self.validity {
+            let mut words = matches.as_words_mut();
+            for (word, bytes) in words.iter_mut().zip(validity.chunks(8)) {
+                let mut valid = [0; 8];
+                valid[..bytes.len()].copy_from_slice(bytes);
+                *word &= u64::from_le_bytes(valid);
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Offset, StringArray};
+    use crate::batch::BitSet;
+    use crate::builder::{Backend, SearcherBuilder};
+    use crate::like::{LikeBuilder, LikeError};
+    use crate::memmem::Finder;
+
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    /// Returns the Arrow layout of `strings`, with offsets of type `O`.
+    fn layout<O: Offset + TryFrom<usize>>(strings: &[&[u8]]) -> (Vec<O>, Vec<u8>) {
+        let mut offsets = vec![0];
+        for string in strings {
+            offsets.push(offsets.last().unwrap() + string.len());
+        }
+        let offsets = offsets
+            .into_iter()
+            .map(|offset| O::try_from(offset).ok().unwrap())
+            .collect();
+        (offsets, strings.concat())
+    }
+
+    /// Returns the bitset of the strings satisfying `predicate`.
+    fn expected(strings: &[&[u8]], predicate: impl Fn(&[u8]) -> bool) -> BitSet {
+        let mut set = BitSet::new(strings.len());
+        for (i, string) in strings.iter().enumerate() {
+            if predicate(string) {
+                set.as_words_mut()[i / 64] |= 1 << (i % 64);
+            }
+        }
+        set
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        memchr::memmem::find(haystack, needle).is_some()
+    }
+
+    fn search<O: Offset + TryFrom<usize>>(strings: &[&[u8]], needles: &[&[u8]]) {
+        let (offsets, values) = layout::<O>(strings);
+        let array = StringArray::new(&offsets, &values);
+        assert_eq!(array.len(), strings.len());
+
+        for &needle in needles {
+            let expected_contains = expected(strings, |s| contains(s, needle));
+            assert_eq!(array.contains(&Finder::new(needle)), expected_contains);
+            if !needle.is_empty() {
+                let searcher = SearcherBuilder::new()
+                    .backend(Backend::Scalar)
+                    .build(needle)
+                    .unwrap();
+                assert_eq!(array.contains(&searcher), expected_contains);
+            }
+
+            let expected_starts_with = expected(strings, |s| s.starts_with(needle));
+            let expected_ends_with = expected(strings, |s| s.ends_with(needle));
+            assert_eq!(array.starts_with(needle), expected_starts_with);
+            assert_eq!(array.ends_with(needle), expected_ends_with);
+
+            let like = |pattern: &[&[u8]]| array.like(&pattern.concat()).unwrap();
+            assert_eq!(like(&[b"%", needle, b"%"]), expected_contains);
+            assert_eq!(like(&[needle, b"%"]), expected_starts_with);
+            assert_eq!(like(&[b"%", needle]), expected_ends_with);
+            assert_eq!(like(&[needle]), expected(strings, |s| s == needle));
+
+            // Every other string is null.
+            let validity = vec![0b0101_0101; strings.len().div_ceil(8)];
+            let array = array.with_validity(&validity);
+            let matches = array.contains(&Finder::new(needle));
+            assert!(matches
+                .iter()
+                .eq(expected_contains.iter().filter(|i| i % 2 == 0)));
+            assert!(array.starts_with(needle).iter().all(|i| i % 2 == 0));
+        }
+    }
+
+    #[test]
+    fn columnar_search() {
+        let strings: [&[u8]; 9] = [b"", b"abc", b"ab", b"c", b"", b"xabcx", b"bca", b"a", b"bc"];
+        let needles: [&[u8]; 6] = [b"", b"a", b"bc", b"abc", b"cab", b"zzz"];
+
+        search::<i32>(&strings, &needles);
+        search::<i64>(&strings, &needles);
+        search::<i32>(&[], &needles);
+        search::<i64>(&[&b""[..]; 100], &needles);
+
+        // Matches straddling the end of a long string.
+        let strings: [&[u8]; 4] = [
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            b"ab",
+            b"aab",
+            b"b",
+        ];
+        search::<i32>(&strings, &[b"aab", b"aaab", b"ba"]);
+    }
+
+    #[test]
+    fn columnar_search_words() {
+        let strings: Vec<&[u8]> = WORDS.split(|&b| b == b'\n').collect();
+        let needles: [&[u8]; 5] = [b"a", b"ab", b"ation", b"\n", b"zzz"];
+
+        search::<i32>(&strings, &needles);
+        search::<i64>(&strings, &needles);
+    }
+
+    #[test]
+    fn columnar_search_sliced() {
+        // Offsets of a slice of a larger array do not start at zero.
+        let values = b"xxabcabcyy";
+        let array = StringArray::new(&[2i32, 5, 5, 8], values);
+        assert_eq!(array.value(0), b"abc");
+        assert_eq!(array.contains(&Finder::new("ca")).count_ones(), 0);
+        assert!(array.contains(&Finder::new("abc")).iter().eq([0, 2]));
+        assert_eq!(array.like(b"%").unwrap().count_ones(), 3);
+    }
+
+    #[test]
+    fn columnar_like() {
+        let array = StringArray::new(&[0i64, 3], b"a%c");
+        assert_eq!(array.like(b"a\\%c").unwrap().to_bytes(), [1]);
+        assert_eq!(array.like(b"%\\%%").unwrap().to_bytes(), [1]);
+        assert_eq!(array.like(b"a_c").unwrap().to_bytes(), [1]);
+        assert_eq!(array.like(b"a%c").unwrap().to_bytes(), [1]);
+        assert_eq!(array.like(b"a\\"), Err(LikeError::TrailingEscape));
+
+        let matcher = LikeBuilder::new()
+            .ascii_case_insensitive(true)
+            .build(b"%A\\%%")
+            .unwrap();
+        assert_eq!(array.like_matches(&matcher).to_bytes(), [1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn columnar_decreasing_offsets() {
+        StringArray::new(&[0i32, 2, 1], b"ab");
+    }
+
+    #[test]
+    #[should_panic]
+    fn columnar_negative_offsets() {
+        StringArray::new(&[-1i32, 1], b"ab");
+    }
+}
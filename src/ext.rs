@@ -0,0 +1,227 @@
+use crate::memmem::{Finder, FinderRev};
+use crate::replace::replace_all;
+use crate::split::{split, Split};
+use crate::SubstringSearch;
+use alloc::vec::Vec;
+
+/// Substring search methods for byte slices, strings and byte vectors, so that
+/// one-off searches do not require constructing a searcher.
+///
+/// The `*_slice` methods create a new searcher on every call using the fastest
+/// backend available at runtime, without caching anything. In hot loops, the
+/// `*_with` methods instead take a prebuilt searcher such as a
+/// `DynamicAvx2Searcher` or a `memmem::Finder`, or a prebuilt
+/// `memmem::FinderRev` for `rfind_with`.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::ext::SliceSearchExt;
+///
+/// assert!("Lorem ipsum dolor".contains_slice("ipsum"));
+/// assert_eq!(b"a, b, c".rfind_slice(", "), Some(4));
+/// assert_eq!("a, b, c".replace_slice(", ", "|"), b"a|b|c");
+/// ```
+pub trait SliceSearchExt {
+    /// Returns whether `needle` occurs within `self`.
+    fn contains_slice<B: ?Sized + AsRef<[u8]>>(&self, needle: &B) -> bool;
+
+    /// Returns the position of the leftmost match of `needle` within `self`,
+    /// if any.
+    fn find_slice<B: ?Sized + AsRef<[u8]>>(&self, needle: &B) -> Option<usize>;
+
+    /// Returns the position of the rightmost match of `needle` within `self`,
+    /// if any, searching from right to left using `memchr::memmem::rfind`.
+    fn rfind_slice<B: ?Sized + AsRef<[u8]>>(&self, needle: &B) -> Option<usize>;
+
+    /// Returns an iterator over the parts of `self` separated by the
+    /// non-overlapping matches of `needle`, with the same semantics as
+    /// `str::split`.
+    fn split_slice<'a, B: ?Sized + AsRef<[u8]>>(&'a self, needle: &'a B) -> Split<'a, Finder<'a>>;
+
+    /// Returns a copy of `self` where all the non-overlapping matches of
+    /// `needle` are replaced with `replacement`, with the same semantics as
+    /// `str::replace`.
+    fn replace_slice<B: ?Sized + AsRef<[u8]>, R: ?Sized + AsRef<[u8]>>(
+        &self,
+        needle: &B,
+        replacement: &R,
+    ) -> Vec<u8>;
+
+    /// Same as `contains_slice` but uses a prebuilt `searcher`.
+    fn contains_with<S: ?Sized + SubstringSearch>(&self, searcher: &S) -> bool;
+
+    /// Same as `find_slice` but uses a prebuilt `searcher`.
+    fn find_with<S: ?Sized + SubstringSearch>(&self, searcher: &S) -> Option<usize>;
+
+    /// Same as `rfind_slice` but uses a prebuilt reverse `finder`, since
+    /// searchers only search forwards.
+    fn rfind_with(&self, finder: &FinderRev<'_>) -> Option<usize>;
+
+    /// Same as `split_slice` but uses a prebuilt `searcher`.
+    fn split_with<'a, S: ?Sized + SubstringSearch>(&'a self, searcher: &'a S) -> Split<'a, &'a S>;
+
+    /// Same as `replace_slice` but uses a prebuilt `searcher`.
+    fn replace_with<S: ?Sized + SubstringSearch, R: ?Sized + AsRef<[u8]>>(
+        &self,
+        searcher: &S,
+        replacement: &R,
+    ) -> Vec<u8>;
+}
+
+macro_rules! impl_slice_search_ext {
+    ($($ty:ty),+) => {
+        $(
+            impl SliceSearchExt for $ty {
+                fn contains_slice<B: ?Sized + AsRef<[u8]>>(&self, needle: &B) -> bool {
+                    self.contains_with(&Finder::new(needle))
+                }
+
+                fn find_slice<B: ?Sized + AsRef<[u8]>>(&self, needle: &B) -> Option<usize> {
+                    self.find_with(&Finder::new(needle))
+                }
+
+                fn rfind_slice<B: ?Sized + AsRef<[u8]>>(&self, needle: &B) -> Option<usize> {
+                    memchr::memmem::rfind(self.as_ref(), needle.as_ref())
+                }
+
+                fn split_slice<'a, B: ?Sized + AsRef<[u8]>>(
+                    &'a self,
+                    needle: &'a B,
+                ) -> Split<'a, Finder<'a>> {
+                    split(Finder::new(needle), self.as_ref())
+                }
+
+                fn replace_slice<B: ?Sized + AsRef<[u8]>, R: ?Sized + AsRef<[u8]>>(
+                    &self,
+                    needle: &B,
+                    replacement: &R,
+                ) -> Vec<u8> {
+                    self.replace_with(&Finder::new(needle), replacement)
+                }
+
+                fn contains_with<S: ?Sized + SubstringSearch>(&self, searcher: &S) -> bool {
+                    searcher.search_in(self.as_ref())
+                }
+
+                fn find_with<S: ?Sized + SubstringSearch>(&self, searcher: &S) -> Option<usize> {
+                    searcher.find(self.as_ref())
+                }
+
+                fn rfind_with(&self, finder: &FinderRev<'_>) -> Option<usize> {
+                    finder.rfind(self.as_ref())
+                }
+
+                fn split_with<'a, S: ?Sized + SubstringSearch>(
+                    &'a self,
+                    searcher: &'a S,
+                ) -> Split<'a, &'a S> {
+                    split(searcher, self.as_ref())
+                }
+
+                fn replace_with<S: ?Sized + SubstringSearch, R: ?Sized + AsRef<[u8]>>(
+                    &self,
+                    searcher: &S,
+                    replacement: &R,
+                ) -> Vec<u8> {
+                    replace_all(searcher, self.as_ref(), replacement.as_ref())
+                }
+            }
+        )+
+    };
+}
+
+impl_slice_search_ext!([u8], str, Vec<u8>);
+
+#[cfg(test)]
+mod tests {
+    use super::SliceSearchExt;
+    use crate::memmem::FinderRev;
+    use crate::MemchrSearcher;
+
+    static WORDS: &str = include_str!("../data/words.txt");
+
+    fn search(haystack: &str, needle: &str) {
+        let bytes = haystack.as_bytes();
+
+        assert_eq!(haystack.contains_slice(needle), haystack.contains(needle));
+        assert_eq!(haystack.find_slice(needle), haystack.find(needle));
+        assert_eq!(haystack.rfind_slice(needle), haystack.rfind(needle));
+        assert_eq!(bytes.to_vec().find_slice(needle), haystack.find(needle));
+
+        assert!(haystack
+            .split_slice(needle)
+            .eq(haystack.split(needle).map(str::as_bytes)));
+        assert_eq!(
+            haystack.replace_slice(needle, "-"),
+            haystack.replace(needle, "-").into_bytes()
+        );
+    }
+
+    #[test]
+    fn ext_search() {
+        let haystacks = [
+            "",
+            "a",
+            "aaa",
+            "abab",
+            "Lorem ipsum dolor sit amet, consectetur adipiscing elit",
+        ];
+        let needles = ["", "a", "aa", "ab", "ipsum", "consectetur"];
+
+        for haystack in haystacks {
+            for needle in needles {
+                search(haystack, needle);
+            }
+        }
+    }
+
+    #[test]
+    fn ext_search_words() {
+        for needle in ["\n", "e", "ing", "ation\n", "zzz"] {
+            search(WORDS, needle);
+        }
+    }
+
+    #[test]
+    fn ext_search_with() {
+        let searcher = MemchrSearcher::new(b'\n');
+        let haystack = WORDS.as_bytes();
+
+        assert!(haystack.contains_with(&searcher));
+        assert_eq!(haystack.find_with(&searcher), WORDS.find('\n'));
+        assert_eq!(
+            haystack.rfind_with(&FinderRev::new("\n")),
+            WORDS.rfind('\n')
+        );
+        assert!(haystack
+            .split_with(&searcher)
+            .eq(WORDS.split('\n').map(str::as_bytes)));
+        assert_eq!(
+            haystack.replace_with(&searcher, " "),
+            WORDS.replace('\n', " ").into_bytes()
+        );
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn ext_search_with_dynamic_avx2() {
+        use crate::x86::DynamicAvx2Searcher;
+
+        if !is_x86_feature_detected!("avx2") {
+            return;
+        }
+
+        let searcher = unsafe { DynamicAvx2Searcher::new(&b"ation"[..]) };
+        let haystack = WORDS.as_bytes();
+
+        assert_eq!(haystack.find_with(&searcher), WORDS.find("ation"));
+        assert_eq!(
+            haystack.rfind_with(&FinderRev::new("ation")),
+            WORDS.rfind("ation")
+        );
+        assert!(haystack
+            .split_with(&searcher)
+            .eq(WORDS.split("ation").map(str::as_bytes)));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::builder::{candidate_searcher, CandidateSearch};
+use alloc::{boxed::Box, vec::Vec};
+
+/// Returns whether `a` and `b` are within a Levenshtein distance of one.
+#[inline]
+fn within_one_edit(a: &[u8], b: &[u8]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+
+    match long.len() - short.len() {
+        0 => {
+            let mut mismatches = short.iter().zip(long).filter(|(a, b)| a != b);
+            mismatches.next();
+            mismatches.next().is_none()
+        }
+        1 => {
+            let prefix = short.iter().zip(long).take_while(|(a, b)| a == b).count();
+            short[prefix..] == long[prefix + 1..]
+        }
+        _ => false,
+    }
+}
+
+/// Fuzzy searcher reporting every position where a substring within a
+/// Levenshtein distance of one of the needle starts, i.e. allowing a single
+/// inserted, deleted or substituted byte.
+///
+/// A single edit can only affect one of the two halves of the needle, so any
+/// approximate match must either start with the first half or contain the
+/// second half shifted by at most one byte from its original offset. Both
+/// halves are searched for using the fastest searcher available at runtime,
+/// and the substrings surrounding every candidate position of a half are then
+/// verified.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::fuzzy::FuzzySearcher;
+///
+/// let searcher = FuzzySearcher::new(b"script");
+///
+/// assert!(searcher.search_in(b"<scrpt>alert(1)</scrpt>"));
+/// assert_eq!(searcher.find_matches_in(b"<scrpt>"), [1]);
+/// ```
+pub struct FuzzySearcher {
+    needle: Box<[u8]>,
+    first: Box<dyn CandidateSearch + Send + Sync>,
+    second: Box<dyn CandidateSearch + Send + Sync>,
+}
+
+impl FuzzySearcher {
+    /// Creates a new searcher for `needle`, splitting it into two halves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `needle` is shorter than two bytes.
+    pub fn new(needle: &[u8]) -> Self {
+        // A single byte is within one edit of the empty string, which would
+        // match at every position.
+        assert!(needle.len() >= 2);
+
+        let (first, second) = needle.split_at(needle.len() / 2);
+
+        Self {
+            needle: needle.into(),
+            first: candidate_searcher(Box::<[u8]>::from(first)),
+            second: candidate_searcher(Box::<[u8]>::from(second)),
+        }
+    }
+
+    /// Returns whether any substring of `haystack` starting at `start` is
+    /// within one edit of the needle.
+    #[inline]
+    fn verify(&self, haystack: &[u8], start: usize) -> bool {
+        let size = self.needle.len();
+        let rest = &haystack[start..];
+
+        (size - 1..=size + 1)
+            .filter(|&end| end <= rest.len())
+            .any(|end| within_one_edit(&rest[..end], &self.needle))
+    }
+
+    /// Calls `f` with every position of `haystack` at which a substring within
+    /// one edit of the needle starts, stopping as soon as `f` returns `true`.
+    /// Positions are neither ordered nor unique.
+    fn search_matches_in<F: FnMut(usize) -> bool>(&self, haystack: &[u8], f: &mut F) -> bool {
+        let size = self.needle.len();
+        if haystack.len() < size - 1 {
+            return false;
+        }
+
+        // The shortest possible match is a single deletion away from the
+        // needle.
+        let last = haystack.len() - (size - 1);
+
+        // Substrings are verified as a whole, so candidates which do not match
+        // a half exactly are only reported if they are within one edit.
+        let found = self.first.search_candidates_in(haystack, &mut |start| {
+            start <= last && self.verify(haystack, start) && f(start)
+        });
+        if found {
+            return true;
+        }
+
+        let offset = self.first.needle().len();
+        self.second.search_candidates_in(haystack, &mut |position| {
+            // The first half may have lost or gained a byte before the second
+            // half was found.
+            let end = match (position + 1).checked_sub(offset) {
+                Some(end) => end,
+                None => return false,
+            };
+            for start in end.saturating_sub(2)..=end {
+                if start <= last && self.verify(haystack, start) && f(start) {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    /// Returns the sorted positions where a substring within one edit of the
+    /// needle starts.
+    pub fn find_matches_in(&self, haystack: &[u8]) -> Vec<usize> {
+        let mut positions = Vec::new();
+        self.search_matches_in(haystack, &mut |position| {
+            positions.push(position);
+            false
+        });
+
+        // Matches can be found through both halves and several candidates.
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Returns whether a substring within one edit of the needle occurs in
+    /// `haystack`.
+    pub fn search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FuzzySearcher;
+
+    /// Computes the Levenshtein distance between `a` and `b` using the classic
+    /// dynamic programming algorithm.
+    fn levenshtein(a: &[u8], b: &[u8]) -> usize {
+        let mut previous = (0..=b.len()).collect::<Vec<_>>();
+        let mut current = vec![0; b.len() + 1];
+
+        for (i, x) in a.iter().enumerate() {
+            current[0] = i + 1;
+            for (j, y) in b.iter().enumerate() {
+                let substitution = previous[j] + usize::from(x != y);
+                current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+
+        previous[b.len()]
+    }
+
+    /// Naive implementation of fuzzy search within a Levenshtein distance of
+    /// one.
+    fn fuzzy_matches(haystack: &[u8], needle: &[u8]) -> Vec<usize> {
+        (0..=haystack.len())
+            .filter(|&start| {
+                // Longer substrings differ by more than one byte in length.
+                let limit = haystack.len().min(start + needle.len() + 1);
+                (start..=limit).any(|end| levenshtein(&haystack[start..end], needle) <= 1)
+            })
+            .collect()
+    }
+
+    fn search(haystack: &[u8], needle: &[u8]) {
+        let searcher = FuzzySearcher::new(needle);
+
+        for end in 0..=haystack.len() {
+            let haystack = &haystack[..end];
+            let matches = fuzzy_matches(haystack, needle);
+
+            assert_eq!(
+                searcher.find_matches_in(haystack),
+                matches,
+                "{:?} in {:?}",
+                needle,
+                haystack,
+            );
+            assert_eq!(searcher.search_in(haystack), !matches.is_empty());
+        }
+    }
+
+    #[test]
+    fn fuzzy_search() {
+        search(b"ab", b"ab");
+
+        search(b"foo", b"fo");
+
+        search(b"fo", b"foo");
+
+        search(b"union sel ect", b"select");
+
+        search(b"<scrpt>alert(1)</script>", b"script");
+
+        search(b"<sccript>alert(1)</script>", b"script");
+
+        search(b"<xscript>alert(1)</script>", b"script");
+
+        search(
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            b"aaba",
+        );
+
+        search(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"consetetur",
+        );
+
+        search(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"ut",
+        );
+    }
+
+    #[test]
+    fn fuzzy_search_random() {
+        // Simple linear congruential generator producing haystacks and needles
+        // over a small alphabet, so that approximate matches are frequent.
+        let mut state = 0x2545_f491_u32;
+        let mut random = |bound: u32| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) % bound
+        };
+
+        for _ in 0..200 {
+            let haystack = (0..random(80))
+                .map(|_| b"abc"[random(3) as usize])
+                .collect::<Vec<_>>();
+            let needle = (0..2 + random(6))
+                .map(|_| b"abc"[random(3) as usize])
+                .collect::<Vec<_>>();
+
+            let searcher = FuzzySearcher::new(&needle);
+            assert_eq!(
+                searcher.find_matches_in(&haystack),
+                fuzzy_matches(&haystack, &needle),
+                "{:?} in {:?}",
+                needle,
+                haystack,
+            );
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use crate::{
+    Needle, NeedleSnapshot, NeedleWithSize, Searcher, SearcherError, VectorHash, WordBoundary,
+};
+
+/// Generic SIMD register type which can be implemented for vector types not
+/// supported by this crate, such as emulated vectors or new instruction set
+/// extensions, to be used with `GenericSearcher`.
+///
+/// # Safety
+///
+/// `GenericSearcher` relies on implementations upholding the following
+/// invariants to avoid reading out of bounds of the haystack:
+///
+/// - `LANES` must be between 1 and 32, inclusive.
+/// - `load` must read at most `LANES` bytes starting at the given pointer,
+///   without any alignment requirement.
+/// - `to_bitmask` must set bit `i` of the result if and only if lane `i` of the
+///   mask is set. Bits above `LANES` are ignored.
+/// - All other methods must be safe to call with any arguments, which implies
+///   that any target feature they rely on is always available.
+pub unsafe trait Vector: Copy {
+    /// Number of byte lanes in the vector.
+    const LANES: usize;
+
+    /// Result of a lane-wise comparison between two vectors.
+    type Mask: Copy;
+
+    /// Returns a vector with every lane set to `a`.
+    fn splat(a: u8) -> Self;
+
+    /// Loads `LANES` bytes starting at `a` into a vector.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `a` is valid for reads of `LANES` bytes. It
+    /// does not need to be aligned.
+    unsafe fn load(a: *const u8) -> Self;
+
+    /// Compares each lane of `a` and `b` for equality.
+    fn lanes_eq(a: Self, b: Self) -> Self::Mask;
+
+    /// Computes the bitwise AND of two masks.
+    fn bitwise_and(a: Self::Mask, b: Self::Mask) -> Self::Mask;
+
+    /// Packs a mask into an integer with one bit per lane, where the first lane
+    /// corresponds to the least significant bit.
+    fn to_bitmask(a: Self::Mask) -> u32;
+}
+
+/// Adapts a public `Vector` to the vector trait used internally by the search
+/// algorithm.
+#[derive(Clone, Copy)]
+struct Generic<V>(V);
+
+impl<V: Vector> crate::Vector for Generic<V> {
+    const LANES: usize = V::LANES;
+    type Mask = V::Mask;
+
+    #[inline(always)]
+    unsafe fn splat(a: u8) -> Self {
+        Self(V::splat(a))
+    }
+
+    #[inline(always)]
+    unsafe fn load(a: *const u8) -> Self {
+        Self(V::load(a))
+    }
+
+    #[inline(always)]
+    unsafe fn lanes_eq(a: Self, b: Self) -> Self::Mask {
+        V::lanes_eq(a.0, b.0)
+    }
+
+    #[inline(always)]
+    unsafe fn bitwise_and(a: Self::Mask, b: Self::Mask) -> Self::Mask {
+        V::bitwise_and(a, b)
+    }
+
+    #[inline(always)]
+    unsafe fn to_bitmask(a: Self::Mask) -> u32 {
+        // Lanes past the end of the vector must never be reported.
+        V::to_bitmask(a) & (u32::MAX >> (32 - V::LANES))
+    }
+}
+
+/// Single-substring searcher using the same algorithm as the architecture
+/// specific searchers, such as `Avx2Searcher`, but generic over a user-provided
+/// `Vector` implementation.
+///
+/// Unlike the architecture specific searchers, a single vector size is used, so
+/// haystacks too short to fill one vector are searched using a scalar version
+/// of the algorithm.
+pub struct GenericSearcher<V: Vector, N: Needle> {
+    position: usize,
+    size: usize,
+    hash: VectorHash<Generic<V>>,
+    needle: N,
+}
+
+impl<V: Vector, N: Needle> GenericSearcher<V, N> {
+    /// Creates a new searcher for `needle`. By default, `position` is set to
+    /// the last character in the needle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `needle` is empty, if the associated `SIZE` constant does not
+    /// correspond to the actual size of `needle` or if `V::LANES` is not
+    /// between 1 and 32. Searching also panics if the size of `needle` has
+    /// changed since then.
+    pub fn new(needle: N) -> Self {
+        // Wrapping prevents panicking on unsigned integer underflow when
+        // `needle` is empty.
+        let position = needle.size().wrapping_sub(1);
+        Self::with_position(needle, position)
+    }
+
+    /// Same as `new` but allows additionally specifying the `position` to use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `needle` is empty, if `position` is not a valid index for
+    /// `needle`, if the associated `SIZE` constant does not correspond to the
+    /// actual size of `needle` or if `V::LANES` is not between 1 and 32.
+    pub fn with_position(needle: N, position: usize) -> Self {
+        match Self::try_with_position(needle, position) {
+            Ok(searcher) => searcher,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Same as `new` but returns an error instead of panicking if `needle` or
+    /// `V::LANES` is invalid.
+    pub fn try_new(needle: N) -> Result<Self, SearcherError> {
+        // Wrapping prevents panicking on unsigned integer underflow when
+        // `needle` is empty.
+        let position = needle.size().wrapping_sub(1);
+        Self::try_with_position(needle, position)
+    }
+
+    /// Same as `with_position` but returns an error instead of panicking if
+    /// `needle`, `position` or `V::LANES` is invalid.
+    pub fn try_with_position(needle: N, position: usize) -> Result<Self, SearcherError> {
+        if !(1..=32).contains(&V::LANES) {
+            return Err(SearcherError::UnsupportedLanes(V::LANES));
+        }
+
+        SearcherError::check(&needle, position)?;
+
+        let bytes = needle.as_bytes();
+
+        // Splatting has no safety requirements for public vector types.
+        let hash = unsafe { VectorHash::new(bytes[0], bytes[position]) };
+
+        Ok(Self {
+            position,
+            size: bytes.len(),
+            hash,
+            needle,
+        })
+    }
+
+    #[inline]
+    fn search_filtered_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
+        // The needle is only borrowed once, so that its size cannot change
+        // during the search.
+        let needle = &NeedleSnapshot::new(&self.needle, self.size);
+
+        if haystack.len() <= needle.size() {
+            return crate::short_search_in(needle, self.position, haystack, verify, f);
+        }
+
+        let position = self.position();
+        let end = haystack.len() - needle.size() + 1;
+
+        if end < V::LANES {
+            let bytes = needle.as_bytes();
+            return (0..end).any(|i| {
+                haystack[i] == bytes[0]
+                    && haystack[i + position] == bytes[position]
+                    && (!verify || haystack[i..].starts_with(bytes))
+                    && f(i)
+            });
+        }
+
+        // Every chunk loaded by the search starts before `end`, so that it
+        // fits within the haystack even when offset by `position`.
+        unsafe {
+            crate::vector_search_in_default_version(
+                needle, position, haystack, end, &self.hash, verify, f,
+            )
+        }
+    }
+
+    #[inline]
+    fn search_matches_in<F: FnMut(usize) -> bool>(&self, haystack: &[u8], f: &mut F) -> bool {
+        self.search_filtered_in(haystack, true, f)
+    }
+
+    /// Inlined version of `search_in` for hot call sites.
+    #[inline]
+    pub fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+
+    /// Performs a substring search for the `needle` within `haystack`.
+    pub fn search_in(&self, haystack: &[u8]) -> bool {
+        self.inlined_search_in(haystack)
+    }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    pub fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        let size = self.size;
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, size)
+        })
+    }
+
+    /// Calls `verify` with every candidate position of `haystack` at which
+    /// both the first byte of the needle and the byte at `position` match, in
+    /// increasing order, stopping as soon as `verify` returns `true`. Returns
+    /// whether any candidate was accepted.
+    ///
+    /// Every candidate leaves room for a full needle, i.e.
+    /// `candidate + needle.len() <= haystack.len()`.
+    pub fn search_candidates_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut verify: F,
+    ) -> bool {
+        self.search_filtered_in(haystack, false, &mut verify)
+    }
+}
+
+impl<V: Vector, N: Needle> Searcher<N> for GenericSearcher<V, N> {
+    fn needle(&self) -> &N {
+        &self.needle
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<V: Vector, N: Needle> crate::SubstringSearch for GenericSearcher<V, N> {
+    fn needle(&self) -> &[u8] {
+        self.needle.as_bytes()
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        self.inlined_search_in(haystack)
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let mut found = None;
+        self.search_matches_in(haystack, &mut |position| {
+            found = Some(position);
+            true
+        });
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GenericSearcher, Vector};
+    use crate::{Needle, SearcherError, WordBoundary};
+    use std::cell::Cell;
+
+    /// Vector emulated using a plain array, as could be done for an
+    /// architecture without SIMD support.
+    #[derive(Clone, Copy)]
+    struct Emulated<const LANES: usize>([u8; LANES]);
+
+    unsafe impl<const LANES: usize> Vector for Emulated<LANES> {
+        const LANES: usize = LANES;
+        type Mask = [bool; LANES];
+
+        fn splat(a: u8) -> Self {
+            Self([a; LANES])
+        }
+
+        unsafe fn load(a: *const u8) -> Self {
+            Self(std::ptr::read_unaligned(a as *const [u8; LANES]))
+        }
+
+        fn lanes_eq(a: Self, b: Self) -> Self::Mask {
+            std::array::from_fn(|i| a.0[i] == b.0[i])
+        }
+
+        fn bitwise_and(a: Self::Mask, b: Self::Mask) -> Self::Mask {
+            std::array::from_fn(|i| a[i] && b[i])
+        }
+
+        fn to_bitmask(a: Self::Mask) -> u32 {
+            a.iter()
+                .enumerate()
+                .fold(0, |bitmask, (i, &lane)| bitmask | (u32::from(lane) << i))
+        }
+    }
+
+    macro_rules! impl_test_searcher {
+        ($name:ident, $lanes:literal) => {
+            type $name<N> = GenericSearcher<Emulated<$lanes>, N>;
+
+            impl crate::tests::TestSearcher for $name<&[u8]> {
+                fn with_position(needle: &'static [u8], position: usize) -> Self {
+                    GenericSearcher::with_position(needle, position)
+                }
+
+                fn search_in(&self, haystack: &[u8]) -> bool {
+                    GenericSearcher::search_in(self, haystack)
+                }
+
+                fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+                    GenericSearcher::search_word_in(self, haystack, boundary)
+                }
+
+                fn search_candidates_in(
+                    &self,
+                    haystack: &[u8],
+                    verify: &mut dyn FnMut(usize) -> bool,
+                ) -> bool {
+                    GenericSearcher::search_candidates_in(self, haystack, verify)
+                }
+            }
+        };
+    }
+
+    impl_test_searcher!(Emulated1Searcher, 1);
+    impl_test_searcher!(Emulated8Searcher, 8);
+    impl_test_searcher!(Emulated32Searcher, 32);
+
+    crate::generate_tests!(emulated_1_searcher, Emulated1Searcher);
+    crate::generate_tests!(emulated_8_searcher, Emulated8Searcher);
+    crate::generate_tests!(emulated_32_searcher, Emulated32Searcher);
+
+    #[test]
+    fn generic_try_with_position() {
+        assert!(Emulated8Searcher::try_new(&b"foo"[..]).is_ok());
+
+        assert_eq!(
+            Emulated8Searcher::try_new(&b""[..]).err(),
+            Some(SearcherError::EmptyNeedle)
+        );
+        assert_eq!(
+            Emulated8Searcher::try_with_position(&b"foo"[..], 3).err(),
+            Some(SearcherError::PositionOutOfRange {
+                position: 3,
+                size: 3
+            })
+        );
+        assert_eq!(
+            GenericSearcher::<Emulated<33>, _>::try_new(&b"foo"[..]).err(),
+            Some(SearcherError::UnsupportedLanes(33))
+        );
+    }
+
+    /// Needle whose bytes can be changed after constructing a searcher.
+    struct ShrinkingNeedle {
+        shrunk: Cell<bool>,
+    }
+
+    impl Needle for ShrinkingNeedle {
+        const SIZE: Option<usize> = Some(6);
+
+        fn as_bytes(&self) -> &[u8] {
+            if self.shrunk.get() {
+                b"a"
+            } else {
+                b"abcdef"
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "needle size changed")]
+    fn generic_needle_size_changed() {
+        let needle = ShrinkingNeedle {
+            shrunk: Cell::new(false),
+        };
+        let searcher = Emulated8Searcher::new(&needle);
+        assert!(searcher.search_in(b"xxabcdefxxxxxxxxxxxx"));
+
+        needle.shrunk.set(true);
+        searcher.search_in(b"xxabcdefxxxxxxxxxxxx");
+    }
+}
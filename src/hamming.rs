@@ -0,0 +1,207 @@
+use crate::builder::{candidate_searcher, CandidateSearch};
+use alloc::{boxed::Box, vec::Vec};
+
+/// Approximate searcher reporting every position where the needle matches with
+/// at most a given number of mismatching bytes, i.e. within a given Hamming
+/// distance.
+///
+/// By the pigeonhole principle, any window matching the needle with at most `k`
+/// mismatches must match at least one of `k + 1` disjoint pieces of the needle
+/// exactly. Each piece is searched for using the fastest searcher available at
+/// runtime, and the window surrounding every candidate position of a piece is
+/// then verified by counting mismatches.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::hamming::HammingSearcher;
+///
+/// let searcher = HammingSearcher::new(b"se1ect", 1);
+///
+/// assert!(searcher.search_in(b"select * from users"));
+/// assert_eq!(searcher.find_matches_in(b"select * from users"), [0]);
+/// ```
+pub struct HammingSearcher {
+    needle: Box<[u8]>,
+    mismatches: usize,
+    pieces: Vec<(usize, Box<dyn CandidateSearch + Send + Sync>)>,
+}
+
+impl HammingSearcher {
+    /// Creates a new searcher for `needle` allowing up to `mismatches`
+    /// mismatching bytes, splitting it into `mismatches + 1` pieces of nearly
+    /// equal size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mismatches` is not smaller than the size of `needle`, which
+    /// in particular means that `needle` cannot be empty.
+    pub fn new(needle: &[u8], mismatches: usize) -> Self {
+        // This also ensures that the needle is not empty and that each of the
+        // pieces contains at least one byte.
+        assert!(mismatches < needle.len());
+
+        let count = mismatches + 1;
+        let pieces = (0..count)
+            .map(|i| {
+                let start = i * needle.len() / count;
+                let end = (i + 1) * needle.len() / count;
+                let piece: Box<[u8]> = needle[start..end].into();
+                (start, candidate_searcher(piece))
+            })
+            .collect();
+
+        Self {
+            needle: needle.into(),
+            mismatches,
+            pieces,
+        }
+    }
+
+    #[inline]
+    fn verify(&self, window: &[u8]) -> bool {
+        let mut mismatches = 0;
+        for (a, b) in window.iter().zip(self.needle.iter()) {
+            if a != b {
+                mismatches += 1;
+                if mismatches > self.mismatches {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Calls `f` with the position of every window of `haystack` which matches
+    /// the needle with at most `mismatches` mismatching bytes, stopping as soon
+    /// as `f` returns `true`. Positions are neither ordered nor unique.
+    fn search_matches_in<F: FnMut(usize) -> bool>(&self, haystack: &[u8], f: &mut F) -> bool {
+        let size = self.needle.len();
+        if haystack.len() < size {
+            return false;
+        }
+
+        for (offset, searcher) in &self.pieces {
+            // Restrict the haystack so that every candidate of the piece at
+            // position `i` corresponds to a full window starting at `i`. The
+            // window is verified as a whole, so candidates which do not match
+            // the piece exactly are only reported if the window matches.
+            let piece = searcher.needle().len();
+            let pieces = &haystack[*offset..haystack.len() - (size - offset - piece)];
+
+            let found = searcher.search_candidates_in(pieces, &mut |position| {
+                self.verify(&haystack[position..position + size]) && f(position)
+            });
+            if found {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns the sorted positions of every match of the needle with at most
+    /// `mismatches` mismatching bytes.
+    pub fn find_matches_in(&self, haystack: &[u8]) -> Vec<usize> {
+        let mut positions = Vec::new();
+        self.search_matches_in(haystack, &mut |position| {
+            positions.push(position);
+            false
+        });
+
+        // Windows can be found through several of their pieces.
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Returns whether the needle matches anywhere in `haystack` with at most
+    /// `mismatches` mismatching bytes.
+    pub fn search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HammingSearcher;
+
+    /// Naive implementation of approximate search under the Hamming distance.
+    fn hamming_matches(haystack: &[u8], needle: &[u8], mismatches: usize) -> Vec<usize> {
+        haystack
+            .windows(needle.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                let count = window.iter().zip(needle).filter(|(a, b)| a != b).count();
+                count <= mismatches
+            })
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    fn search(haystack: &[u8], needle: &[u8], mismatches: usize) {
+        let searcher = HammingSearcher::new(needle, mismatches);
+
+        for end in 0..=haystack.len() {
+            let haystack = &haystack[..end];
+            let matches = hamming_matches(haystack, needle, mismatches);
+
+            assert_eq!(
+                searcher.find_matches_in(haystack),
+                matches,
+                "{:?} with {} mismatches in {:?}",
+                needle,
+                mismatches,
+                haystack,
+            );
+            assert_eq!(searcher.search_in(haystack), !matches.is_empty());
+        }
+    }
+
+    #[test]
+    fn hamming_search() {
+        search(b"foo", b"foo", 0);
+
+        search(b"foo", b"fxo", 1);
+
+        search(b"foo", b"xxo", 1);
+
+        search(b"foo", b"xxo", 2);
+
+        search(b"select * from users", b"se1ect", 1);
+
+        search(b"select * from users", b"5e1ect", 1);
+
+        search(b"select * from users", b"5e1ect", 2);
+
+        search(
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            b"aabaa",
+            1,
+        );
+
+        search(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"c0nsectetor",
+            2,
+        );
+
+        search(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"o",
+            0,
+        );
+
+        search(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"ut",
+            1,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn hamming_search_too_many_mismatches() {
+        HammingSearcher::new(b"foo", 3);
+    }
+}
@@ -3,14 +3,104 @@
 //! Muła](http://0x80.pl/articles/simd-strfind.html). For a fast multi-pattern
 //! substring search algorithm, see instead the [`aho-corasick`
 //! crate](https://github.com/BurntSushi/aho-corasick).
+//!
+//! # Crate features
+//!
+//! - `std` (enabled by default): enables `alloc`, runtime CPU feature
+//!   detection and `std::io` integration. Without it, the crate is `no_std`
+//!   and only the searchers themselves are available, with backends selected
+//!   from the target features enabled at compile time.
+//! - `alloc`: enables the searchers and APIs that allocate, such as `memmem`,
+//!   `builder`, `fuzzy`, `hamming` and `near`.
+//! - `rayon`: enables the `parallel` module, searching chunks of a single large
+//!   haystack in parallel.
+//! - `regex-automata`: enables `std` and the `prefilter` module, finding
+//!   matches of `regex-automata` regular expressions with a literal prefix.
+//! - `pattern` (requires nightly): enables `alloc` and the `pattern` module,
+//!   implementing the unstable standard library `Pattern` trait.
+//! - `stdsimd` (requires nightly): enables the `stdsimd` module, with
+//!   searchers using the unstable portable SIMD API of the standard library.
 
 #![warn(missing_docs)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(feature = "stdsimd", feature(portable_simd))]
+#![cfg_attr(feature = "pattern", feature(pattern))]
+
+#[macro_use]
+mod multiversion;
+
+/// Search of one needle within many short haystacks, reporting which of them
+/// match as a bitset.
+#[cfg(feature = "alloc")]
+pub mod batch;
+
+/// Builder API for configuring searchers.
+#[cfg(feature = "alloc")]
+pub mod builder;
+
+/// Search of Arrow-style string arrays, evaluating predicates for every string
+/// into a bitmap.
+#[cfg(feature = "alloc")]
+pub mod columnar;
+
+/// Extension trait for one-off substring searches on byte slices and strings.
+#[cfg(feature = "alloc")]
+pub mod ext;
+
+/// Approximate search for a needle within a Levenshtein distance of one.
+#[cfg(feature = "alloc")]
+pub mod fuzzy;
+
+/// Substring search implementations generic over user-provided vector types.
+pub mod generic;
+
+/// Approximate search for a needle with a bounded number of mismatching bytes.
+#[cfg(feature = "alloc")]
+pub mod hamming;
+
+/// Matching of SQL `LIKE` and shell glob patterns using substring searches.
+#[cfg(feature = "alloc")]
+pub mod like;
+
+/// Substring search with the same interface as `memchr::memmem`, using the
+/// fastest searcher available at runtime.
+#[cfg(feature = "alloc")]
+pub mod memmem;
+
+/// Proximity search for two needles occurring within a given distance of each
+/// other.
+#[cfg(feature = "alloc")]
+pub mod near;
 
 /// Substring search implementations using aarch64 architecture features.
 #[cfg(target_arch = "aarch64")]
 pub mod aarch64;
 
+/// Parallel search of a single large haystack using `rayon`.
+#[cfg(feature = "rayon")]
+pub mod parallel;
+
+/// Implementation of the standard library `Pattern` trait for string searches.
+#[cfg(feature = "pattern")]
+pub mod pattern;
+
+/// Prefilter for `regex-automata` regular expressions with a literal prefix.
+#[cfg(feature = "regex-automata")]
+pub mod prefilter;
+
+/// Boolean queries over several needles, evaluated with short-circuiting.
+#[cfg(feature = "alloc")]
+pub mod query;
+
+/// Replacement of all the matches of a searcher within a haystack.
+#[cfg(feature = "alloc")]
+pub mod replace;
+
+/// Iterators over the parts of a haystack separated by the matches of a
+/// searcher.
+#[cfg(feature = "alloc")]
+pub mod split;
+
 /// Substring search implementations using generic stdsimd features.
 #[cfg(feature = "stdsimd")]
 pub mod stdsimd;
@@ -23,12 +113,13 @@ pub mod x86;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm32;
 
-use memchr::memchr;
-use std::rc::Rc;
-use std::sync::Arc;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-#[macro_use]
-mod multiversion;
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, rc::Rc, sync::Arc, vec::Vec};
+use core::marker::PhantomData;
+use memchr::{memchr, memchr_iter};
 
 /// Needle that can be searched for within a haystack. It allows specialized
 /// searcher implementations for needle sizes known at compile time.
@@ -58,6 +149,7 @@ impl Needle for [u8] {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<N: Needle + ?Sized> Needle for Box<N> {
     const SIZE: Option<usize> = N::SIZE;
 
@@ -67,6 +159,7 @@ impl<N: Needle + ?Sized> Needle for Box<N> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<N: Needle + ?Sized> Needle for Rc<N> {
     const SIZE: Option<usize> = N::SIZE;
 
@@ -76,6 +169,7 @@ impl<N: Needle + ?Sized> Needle for Rc<N> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<N: Needle + ?Sized> Needle for Arc<N> {
     const SIZE: Option<usize> = N::SIZE;
 
@@ -94,6 +188,7 @@ impl<N: Needle + ?Sized> Needle for &N {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Needle for Vec<u8> {
     const SIZE: Option<usize> = None;
 
@@ -116,6 +211,248 @@ trait NeedleWithSize: Needle {
 
 impl<N: Needle + ?Sized> NeedleWithSize for N {}
 
+/// Bytes of a needle obtained by calling `as_bytes` once, so that every size
+/// used by a search is derived from the same slice. Safe searchers accept any
+/// `Needle` implementation, whose `as_bytes` may return different bytes on
+/// every call.
+struct NeedleSnapshot<'a, N: ?Sized> {
+    bytes: &'a [u8],
+    needle: PhantomData<&'a N>,
+}
+
+impl<'a, N: Needle + ?Sized> NeedleSnapshot<'a, N> {
+    /// Takes a snapshot of `needle`, whose size was `size` when constructing
+    /// the searcher.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `needle` has changed.
+    #[inline]
+    fn new(needle: &'a N, size: usize) -> Self {
+        let bytes = needle.as_bytes();
+        assert_eq!(bytes.len(), size, "needle size changed");
+        Self {
+            bytes,
+            needle: PhantomData,
+        }
+    }
+}
+
+impl<N: Needle + ?Sized> Needle for NeedleSnapshot<'_, N> {
+    // The size was checked against `N::SIZE` when constructing the searcher.
+    const SIZE: Option<usize> = N::SIZE;
+
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+/// Error returned by the fallible searcher constructors, such as
+/// `try_with_position`, instead of panicking on invalid arguments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SearcherError {
+    /// The needle is empty, which is not supported by the searcher.
+    EmptyNeedle,
+    /// The `position` is not a valid index for the needle.
+    PositionOutOfRange {
+        /// Requested position.
+        position: usize,
+        /// Size of the needle.
+        size: usize,
+    },
+    /// The `SIZE` constant associated with the needle does not correspond to
+    /// its actual size.
+    SizeMismatch {
+        /// Size declared by the `SIZE` constant.
+        expected: usize,
+        /// Actual size of the needle.
+        actual: usize,
+    },
+    /// The number of lanes of a generic vector type is not supported.
+    UnsupportedLanes(usize),
+    /// The backend forced using a `SearcherBuilder` is not available.
+    #[cfg(feature = "alloc")]
+    UnsupportedBackend(builder::Backend),
+    /// The needle length thresholds of a `SearcherBuilder` are inconsistent.
+    InvalidThresholds {
+        /// Minimum needle length searched using SIMD.
+        min: usize,
+        /// Maximum needle length searched using SIMD.
+        max: Option<usize>,
+    },
+}
+
+impl SearcherError {
+    /// Checks that `needle` is not empty, that its `SIZE` constant is correct
+    /// and that `position` is a valid index for it.
+    fn check<N: Needle + ?Sized>(needle: &N, position: usize) -> Result<(), Self> {
+        let size = needle.as_bytes().len();
+        if size == 0 {
+            return Err(Self::EmptyNeedle);
+        }
+
+        if let Some(expected) = N::SIZE {
+            if expected != size {
+                return Err(Self::SizeMismatch {
+                    expected,
+                    actual: size,
+                });
+            }
+        }
+
+        if position >= size {
+            return Err(Self::PositionOutOfRange { position, size });
+        }
+
+        Ok(())
+    }
+}
+
+impl core::fmt::Display for SearcherError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EmptyNeedle => write!(f, "needle is empty"),
+            Self::PositionOutOfRange { position, size } => write!(
+                f,
+                "position {position} is out of range for needle of size {size}"
+            ),
+            Self::SizeMismatch { expected, actual } => write!(
+                f,
+                "needle has size {actual} but its SIZE constant is {expected}"
+            ),
+            Self::UnsupportedLanes(lanes) => {
+                write!(f, "vectors with {lanes} lanes are not supported")
+            }
+            #[cfg(feature = "alloc")]
+            Self::UnsupportedBackend(backend) => {
+                write!(f, "backend {backend:?} is not available")
+            }
+            Self::InvalidThresholds { min, max } => {
+                write!(f, "invalid SIMD needle length range {min}..={max:?}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SearcherError {}
+
+/// Object-safe interface implemented by all searchers, allowing them to be used
+/// interchangeably in generic code or selected at runtime, for example as a
+/// `Box<dyn SubstringSearch>`.
+pub trait SubstringSearch {
+    /// Returns the needle searched for.
+    fn needle(&self) -> &[u8];
+
+    /// Returns the index of the needle byte used for filtering alongside the
+    /// first byte.
+    fn position(&self) -> usize;
+
+    /// Performs a substring search for the needle within `haystack`.
+    fn search_in(&self, haystack: &[u8]) -> bool;
+
+    /// Returns the position of the leftmost match of the needle within
+    /// `haystack`, if any.
+    fn find(&self, haystack: &[u8]) -> Option<usize>;
+
+    /// Searches each of `haystacks`, setting bit `i` of `matches` if
+    /// `haystacks[i]` contains the needle and clearing it otherwise, along with
+    /// any remaining bit. `matches` is a bitset stored in 64-bit words, least
+    /// significant bit first, as accessed through `batch::BitSet::as_words_mut`.
+    ///
+    /// Each haystack is searched on its own. The AVX2 and SIMD128 searchers
+    /// only enable their target features once for the whole batch, so that
+    /// searching each haystack is inlined instead of going through a call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `matches` has fewer than `haystacks.len()` bits.
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        batch_search_in(haystacks.iter().copied(), matches, |haystack| {
+            self.search_in(haystack)
+        })
+    }
+
+    /// Same as `search_batch_in` but for haystacks stored contiguously in
+    /// `data`, where haystack `i` is `data[offsets[i]..offsets[i + 1]]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `matches` has fewer than `offsets.len() - 1` bits, or if
+    /// `offsets` is decreasing or out of bounds for `data`.
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        batch_search_in(offsets_haystacks(offsets, data), matches, |haystack| {
+            self.search_in(haystack)
+        })
+    }
+}
+
+/// Stores whether each of `haystacks` matches according to `search` into the
+/// `matches` bitset, 64 haystacks at a time.
+#[inline(always)]
+fn batch_search_in<'h, I, F>(haystacks: I, matches: &mut [u64], mut search: F)
+where
+    I: ExactSizeIterator<Item = &'h [u8]>,
+    F: FnMut(&'h [u8]) -> bool,
+{
+    assert!(haystacks.len() <= matches.len() * 64);
+
+    let mut haystacks = haystacks;
+    for word in matches {
+        let mut bits = 0;
+        for (bit, haystack) in haystacks.by_ref().take(64).enumerate() {
+            bits |= (search(haystack) as u64) << bit;
+        }
+        *word = bits;
+    }
+}
+
+/// Returns an iterator over the haystacks stored contiguously in `data` and
+/// delimited by `offsets`.
+#[inline(always)]
+fn offsets_haystacks<'h>(
+    offsets: &'h [usize],
+    data: &'h [u8],
+) -> impl ExactSizeIterator<Item = &'h [u8]> {
+    offsets
+        .windows(2)
+        .map(move |bounds| &data[bounds[0]..bounds[1]])
+}
+
+impl<S: SubstringSearch + ?Sized> SubstringSearch for &S {
+    #[inline]
+    fn needle(&self) -> &[u8] {
+        (**self).needle()
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        (**self).position()
+    }
+
+    #[inline]
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        (**self).search_in(haystack)
+    }
+
+    #[inline]
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        (**self).find(haystack)
+    }
+
+    #[inline]
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        (**self).search_batch_in(haystacks, matches)
+    }
+
+    #[inline]
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        (**self).search_offsets_in(offsets, data, matches)
+    }
+}
+
 /// Single-byte searcher using `memchr` for faster matching.
 pub struct MemchrSearcher(u8);
 
@@ -135,10 +472,96 @@ impl MemchrSearcher {
         memchr(self.0, haystack).is_some()
     }
 
+    #[inline]
+    fn search_matches_in<F: FnMut(usize) -> bool>(&self, haystack: &[u8], f: &mut F) -> bool {
+        memchr_iter(self.0, haystack).any(f)
+    }
+
     /// Performs a substring search for the `needle` within `haystack`.
     pub fn search_in(&self, haystack: &[u8]) -> bool {
         self.inlined_search_in(haystack)
     }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    pub fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, 1)
+        })
+    }
+}
+
+impl SubstringSearch for MemchrSearcher {
+    fn needle(&self) -> &[u8] {
+        core::slice::from_ref(&self.0)
+    }
+
+    fn position(&self) -> usize {
+        0
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        self.inlined_search_in(haystack)
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        memchr(self.0, haystack)
+    }
+}
+
+/// Word boundary mode restricting matches to whole words, i.e. matches which
+/// are neither immediately preceded nor immediately followed by a word byte.
+///
+/// By default, ASCII letters, digits and underscores are considered to be word
+/// bytes, so that searching for `select` finds `select * from` but not
+/// `selection`.
+#[derive(Clone, Debug)]
+pub struct WordBoundary {
+    word: [bool; 256],
+}
+
+impl WordBoundary {
+    /// Creates a new word boundary mode where `word` contains all the bytes
+    /// considered to be part of a word.
+    pub fn new(word: &[u8]) -> Self {
+        let mut table = [false; 256];
+        for &byte in word {
+            table[byte as usize] = true;
+        }
+        Self { word: table }
+    }
+
+    /// Creates a new word boundary mode where ASCII letters, digits and
+    /// underscores are considered to be part of a word.
+    pub fn ascii() -> Self {
+        let mut table = [false; 256];
+        for byte in 0..=255u8 {
+            table[byte as usize] = byte.is_ascii_alphanumeric() || byte == b'_';
+        }
+        Self { word: table }
+    }
+
+    /// Returns whether `byte` is considered to be part of a word.
+    #[inline]
+    pub fn is_word(&self, byte: u8) -> bool {
+        self.word[byte as usize]
+    }
+
+    /// Returns whether a match of `size` bytes at `position` in `haystack` is
+    /// delimited by word boundaries on both sides.
+    #[inline]
+    pub fn is_match(&self, haystack: &[u8], position: usize, size: usize) -> bool {
+        let before = position.checked_sub(1).map(|i| haystack[i]);
+        let after = haystack.get(position + size).copied();
+
+        !before.is_some_and(|byte| self.is_word(byte))
+            && !after.is_some_and(|byte| self.is_word(byte))
+    }
+}
+
+impl Default for WordBoundary {
+    fn default() -> Self {
+        Self::ascii()
+    }
 }
 
 /// Represents a generic SIMD register type.
@@ -189,20 +612,50 @@ impl<T: Vector, V: Vector + From<T>> From<&VectorHash<T>> for VectorHash<V> {
 
 macro_rules! memcmp {
     ($chunk:ident, $needle:ident, $len:literal) => {
-        std::slice::from_raw_parts($chunk, $len) == std::slice::from_raw_parts($needle, $len)
+        core::slice::from_raw_parts($chunk, $len) == core::slice::from_raw_parts($needle, $len)
     };
     ($chunk:ident, $needle:ident, $len:ident) => {
-        std::slice::from_raw_parts($chunk, $len) == std::slice::from_raw_parts($needle, $len)
+        core::slice::from_raw_parts($chunk, $len) == core::slice::from_raw_parts($needle, $len)
     };
 }
 
+/// Compares the bytes at `chunk` with `needle`, skipping the first byte which
+/// has already been matched by the SIMD filter.
+#[inline(always)]
+unsafe fn chunk_eq_needle<N: NeedleWithSize + ?Sized>(chunk: *const u8, needle: &N) -> bool {
+    let chunk = chunk.add(1);
+    let size = needle.size() - 1;
+    let needle = needle.as_bytes().as_ptr().add(1);
+
+    match N::SIZE {
+        Some(0) => unreachable!(),
+        Some(1) => memcmp!(chunk, needle, 0),
+        Some(2) => memcmp!(chunk, needle, 1),
+        Some(3) => memcmp!(chunk, needle, 2),
+        Some(4) => memcmp!(chunk, needle, 3),
+        Some(5) => memcmp!(chunk, needle, 4),
+        Some(6) => memcmp!(chunk, needle, 5),
+        Some(7) => memcmp!(chunk, needle, 6),
+        Some(8) => memcmp!(chunk, needle, 7),
+        Some(9) => memcmp!(chunk, needle, 8),
+        Some(10) => memcmp!(chunk, needle, 9),
+        Some(11) => memcmp!(chunk, needle, 10),
+        Some(12) => memcmp!(chunk, needle, 11),
+        Some(13) => memcmp!(chunk, needle, 12),
+        Some(14) => memcmp!(chunk, needle, 13),
+        Some(15) => memcmp!(chunk, needle, 14),
+        Some(16) => memcmp!(chunk, needle, 15),
+        _ => memcmp!(chunk, needle, size),
+    }
+}
+
 multiversion! {
-unsafe fn vector_search_in_chunk<N: NeedleWithSize + ?Sized, V: Vector>(
-    needle: &N,
+unsafe fn vector_candidates_in_chunk<V: Vector, F: FnMut(usize) -> bool>(
     position: usize,
     hash: &VectorHash<V>,
     start: *const u8,
     mask: u32,
+    f: &mut F,
 ) -> bool {
     let first = V::load(start);
     let last = V::load(start.add(position));
@@ -213,33 +666,9 @@ unsafe fn vector_search_in_chunk<N: NeedleWithSize + ?Sized, V: Vector>(
     let eq = V::bitwise_and(eq_first, eq_last);
     let mut eq = V::to_bitmask(eq) & mask;
 
-    let chunk = start.add(1);
-    let size = needle.size() - 1;
-    let needle = needle.as_bytes().as_ptr().add(1);
-
     while eq != 0 {
-        let chunk = chunk.add(eq.trailing_zeros() as usize);
-        let equal = match N::SIZE {
-            Some(0) => unreachable!(),
-            Some(1) => memcmp!(chunk, needle, 0),
-            Some(2) => memcmp!(chunk, needle, 1),
-            Some(3) => memcmp!(chunk, needle, 2),
-            Some(4) => memcmp!(chunk, needle, 3),
-            Some(5) => memcmp!(chunk, needle, 4),
-            Some(6) => memcmp!(chunk, needle, 5),
-            Some(7) => memcmp!(chunk, needle, 6),
-            Some(8) => memcmp!(chunk, needle, 7),
-            Some(9) => memcmp!(chunk, needle, 8),
-            Some(10) => memcmp!(chunk, needle, 9),
-            Some(11) => memcmp!(chunk, needle, 10),
-            Some(12) => memcmp!(chunk, needle, 11),
-            Some(13) => memcmp!(chunk, needle, 12),
-            Some(14) => memcmp!(chunk, needle, 13),
-            Some(15) => memcmp!(chunk, needle, 14),
-            Some(16) => memcmp!(chunk, needle, 15),
-            _ => memcmp!(chunk, needle, size),
-        };
-        if equal {
+        let lane = eq.trailing_zeros() as usize;
+        if f(lane) {
             return true;
         }
 
@@ -250,35 +679,47 @@ unsafe fn vector_search_in_chunk<N: NeedleWithSize + ?Sized, V: Vector>(
     false
 }}
 
+// Calls `f` with every position within `haystack[..end]` whose first byte and
+// byte at offset `position` match the ones in `hash`, in increasing order,
+// stopping as soon as `f` returns `true`.
 multiversion! {
-pub(crate) unsafe fn vector_search_in<N: NeedleWithSize + ?Sized, V: Vector>(
-    needle: &N,
+unsafe fn vector_candidates_in<V: Vector, F: FnMut(usize) -> bool>(
     position: usize,
     haystack: &[u8],
     end: usize,
     hash: &VectorHash<V>,
+    f: &mut F,
 ) -> bool {
-    debug_assert!(haystack.len() >= needle.size());
+    debug_assert!(haystack.len() >= end + position);
 
+    let mut offset = 0;
     let mut chunks = haystack[..end].chunks_exact(V::LANES);
     for chunk in &mut chunks {
-        if dispatch!(TARGET => vector_search_in_chunk(
-            needle,
+        if dispatch!(TARGET => vector_candidates_in_chunk(
             position,
             hash,
             chunk.as_ptr(),
-            u32::MAX
+            u32::MAX,
+            &mut |lane| f(offset + lane)
         )) {
             return true;
         }
+        offset += V::LANES;
     }
 
     let remainder = chunks.remainder().len();
     if remainder > 0 {
-        let start = haystack.as_ptr().add(end - V::LANES);
+        let offset = end - V::LANES;
+        let start = haystack.as_ptr().add(offset);
         let mask = u32::MAX << (V::LANES - remainder);
 
-        if dispatch!(TARGET => vector_search_in_chunk(needle, position, hash, start, mask)) {
+        if dispatch!(TARGET => vector_candidates_in_chunk(
+            position,
+            hash,
+            start,
+            mask,
+            &mut |lane| f(offset + lane)
+        )) {
             return true;
         }
     }
@@ -286,6 +727,61 @@ pub(crate) unsafe fn vector_search_in<N: NeedleWithSize + ?Sized, V: Vector>(
     false
 }}
 
+// Calls `f` with the position of every match of `needle` within
+// `haystack[..end]`, in increasing order, stopping as soon as `f` returns
+// `true`. If `verify` is `false`, `f` is instead called with every candidate
+// position passing the two-byte filter.
+multiversion! {
+pub(crate) unsafe fn vector_search_in<
+    N: NeedleWithSize + ?Sized,
+    V: Vector,
+    F: FnMut(usize) -> bool
+>(
+    needle: &N,
+    position: usize,
+    haystack: &[u8],
+    end: usize,
+    hash: &VectorHash<V>,
+    verify: bool,
+    f: &mut F,
+) -> bool {
+    debug_assert!(haystack.len() >= needle.size());
+
+    if !verify {
+        return dispatch!(TARGET => vector_candidates_in(position, haystack, end, hash, f));
+    }
+
+    let start = haystack.as_ptr();
+    dispatch!(TARGET => vector_candidates_in(
+        position,
+        haystack,
+        end,
+        hash,
+        &mut |candidate| chunk_eq_needle(start.add(candidate), needle) && f(candidate)
+    ))
+}}
+
+// Same as `vector_search_in` for haystacks which are not longer than `needle`,
+// where the only possible match or candidate is at position zero.
+#[inline]
+fn short_search_in<N: NeedleWithSize + ?Sized, F: FnMut(usize) -> bool>(
+    needle: &N,
+    position: usize,
+    haystack: &[u8],
+    verify: bool,
+    f: &mut F,
+) -> bool {
+    let bytes = needle.as_bytes();
+    let eq = if verify {
+        haystack == bytes
+    } else {
+        haystack.len() == bytes.len()
+            && haystack[0] == bytes[0]
+            && haystack[position] == bytes[position]
+    };
+    eq && f(0)
+}
+
 trait Searcher<N: NeedleWithSize + ?Sized> {
     fn needle(&self) -> &N;
 
@@ -294,7 +790,7 @@ trait Searcher<N: NeedleWithSize + ?Sized> {
 
 #[cfg(test)]
 mod tests {
-    use super::{MemchrSearcher, Needle};
+    use super::{MemchrSearcher, Needle, SubstringSearch, WordBoundary};
 
     fn memchr_search(haystack: &[u8], needle: &[u8]) -> bool {
         MemchrSearcher::new(needle[0]).search_in(haystack)
@@ -330,6 +826,35 @@ mod tests {
         assert!(memchr_search(b"foobarfoo", b"b"));
     }
 
+    #[test]
+    fn memchr_search_word() {
+        let boundary = WordBoundary::default();
+        let searcher = MemchrSearcher::new(b'a');
+
+        assert!(searcher.search_word_in(b"a", &boundary));
+
+        assert!(searcher.search_word_in(b"bab a", &boundary));
+
+        assert!(!searcher.search_word_in(b"bab ab", &boundary));
+    }
+
+    #[test]
+    fn memchr_substring_search() {
+        let searchers: Vec<Box<dyn SubstringSearch>> = vec![
+            Box::new(MemchrSearcher::new(b'o')),
+            Box::new(MemchrSearcher::new(b'x')),
+        ];
+
+        assert_eq!(searchers[0].needle(), b"o");
+        assert_eq!(searchers[0].position(), 0);
+
+        assert_eq!(searchers[0].find(b"foobar"), Some(1));
+        assert!(searchers[0].search_in(b"foobar"));
+
+        assert_eq!(searchers[1].find(b"foobar"), None);
+        assert!(!searchers[1].search_in(b"foobar"));
+    }
+
     #[test]
     fn needle_array_size() {
         use std::rc::Rc;
@@ -365,6 +890,12 @@ mod tests {
     pub(crate) trait TestSearcher {
         fn with_position(needle: &'static [u8], position: usize) -> Self;
         fn search_in(&self, haystack: &[u8]) -> bool;
+        fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool;
+        fn search_candidates_in(
+            &self,
+            haystack: &[u8],
+            verify: &mut dyn FnMut(usize) -> bool,
+        ) -> bool;
     }
 
     fn search<S: TestSearcher>(haystack: &[u8], needle: &'static [u8]) -> bool {
@@ -380,6 +911,8 @@ mod tests {
         result
     }
 
+    /// Generates the common test cases for a searcher implementing
+    /// `TestSearcher`.
     #[macro_export]
     macro_rules! generate_tests {
         ($mod: ident, $name:ident) => {
@@ -415,6 +948,21 @@ mod tests {
                 fn test_search_middle() {
                     $crate::tests::search_middle::<$name<&[u8]>>();
                 }
+
+                #[test]
+                fn test_search_words() {
+                    $crate::tests::search_words::<$name<&[u8]>>();
+                }
+
+                #[test]
+                fn test_substring_search() {
+                    $crate::tests::substring_search::<$name<&[u8]>>();
+                }
+
+                #[test]
+                fn test_search_candidates() {
+                    $crate::tests::search_candidates::<$name<&[u8]>>();
+                }
             }
         };
     }
@@ -542,4 +1090,187 @@ mod tests {
             b"Maecenas commodo posuere orci a consectetur"
         ));
     }
+
+    fn search_word<S: TestSearcher>(
+        haystack: &[u8],
+        needle: &'static [u8],
+        boundary: &WordBoundary,
+    ) -> bool {
+        let result = haystack
+            .windows(needle.len())
+            .enumerate()
+            .any(|(i, window)| window == needle && boundary.is_match(haystack, i, needle.len()));
+
+        for position in 0..needle.len() {
+            let searcher = S::with_position(needle, position);
+            assert_eq!(searcher.search_word_in(haystack, boundary), result);
+        }
+
+        result
+    }
+
+    pub(crate) fn search_words<S: TestSearcher>() {
+        let ascii = WordBoundary::default();
+
+        assert!(search_word::<S>(b"select", b"select", &ascii));
+
+        assert!(search_word::<S>(b"select * from", b"select", &ascii));
+
+        assert!(!search_word::<S>(b"selection", b"select", &ascii));
+
+        assert!(!search_word::<S>(b"preselect", b"select", &ascii));
+
+        assert!(search_word::<S>(b"selection, select", b"select", &ascii));
+
+        assert!(search_word::<S>(b"x-y", b"y", &ascii));
+
+        assert!(!search_word::<S>(b"xy", b"y", &ascii));
+
+        assert!(search_word::<S>(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"consectetur",
+            &ascii
+        ));
+
+        assert!(!search_word::<S>(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"it",
+            &ascii
+        ));
+
+        let custom = WordBoundary::new(b"abcdefghijklmnopqrstuvwxyz-");
+
+        assert!(!search_word::<S>(b"x-y", b"y", &custom));
+
+        assert!(search_word::<S>(b"x_y", b"y", &custom));
+
+        assert!(search_word::<S>(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"Ut",
+            &custom
+        ));
+    }
+
+    fn find<S: TestSearcher + SubstringSearch>(
+        haystack: &[u8],
+        needle: &'static [u8],
+    ) -> Option<usize> {
+        let result = haystack
+            .windows(needle.len())
+            .position(|window| window == needle);
+
+        for position in 0..needle.len() {
+            let searcher = S::with_position(needle, position);
+            let searcher: &dyn SubstringSearch = &searcher;
+
+            assert_eq!(searcher.needle(), needle);
+            assert_eq!(searcher.position(), position);
+            assert_eq!(searcher.find(haystack), result);
+            assert_eq!(searcher.search_in(haystack), result.is_some());
+        }
+
+        result
+    }
+
+    pub(crate) fn substring_search<S: TestSearcher + SubstringSearch>() {
+        assert_eq!(find::<S>(b"", b"x"), None);
+
+        assert_eq!(find::<S>(b"x", b"x"), Some(0));
+
+        assert_eq!(find::<S>(b"xy", b"y"), Some(1));
+
+        assert_eq!(find::<S>(b"foobarfoo", b"foo"), Some(0));
+
+        assert_eq!(find::<S>(b"barfoofoo", b"foo"), Some(3));
+
+        assert_eq!(find::<S>(b"foobarfoo", b"baz"), None);
+
+        assert_eq!(
+            find::<S>(
+                b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+                b"consectetur"
+            ),
+            Some(28)
+        );
+
+        assert_eq!(
+            find::<S>(
+                b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+                b"consequat"
+            ),
+            Some(129)
+        );
+
+        assert_eq!(
+            find::<S>(
+                b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxy",
+                b"xxy"
+            ),
+            Some(73)
+        );
+    }
+
+    fn search_candidate<S: TestSearcher>(haystack: &[u8], needle: &'static [u8]) {
+        for position in 0..needle.len() {
+            let searcher = S::with_position(needle, position);
+
+            let expected = haystack
+                .windows(needle.len())
+                .enumerate()
+                .filter(|(_, window)| {
+                    window[0] == needle[0] && window[position] == needle[position]
+                })
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+
+            let mut candidates = Vec::new();
+            assert!(!searcher.search_candidates_in(haystack, &mut |candidate| {
+                candidates.push(candidate);
+                false
+            }));
+            assert_eq!(candidates, expected);
+
+            // Stops at the first accepted candidate.
+            let mut count = 0;
+            let found = searcher.search_candidates_in(haystack, &mut |_| {
+                count += 1;
+                true
+            });
+            assert_eq!(found, !expected.is_empty());
+            assert_eq!(count, usize::from(found));
+
+            // Verifying candidates against the needle is a regular search.
+            let found = searcher.search_candidates_in(haystack, &mut |candidate| {
+                haystack[candidate..].starts_with(needle)
+            });
+            assert_eq!(found, searcher.search_in(haystack));
+        }
+    }
+
+    pub(crate) fn search_candidates<S: TestSearcher>() {
+        search_candidate::<S>(b"", b"x");
+
+        search_candidate::<S>(b"x", b"x");
+
+        search_candidate::<S>(b"xy", b"xz");
+
+        search_candidate::<S>(b"foobarfoo", b"foo");
+
+        search_candidate::<S>(b"fxofyofzo", b"foo");
+
+        search_candidate::<S>(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"consectetur",
+        );
+
+        search_candidate::<S>(
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"ut",
+        );
+
+        search_candidate::<S>(
+            b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            b"xxyxx",
+        );
+    }
 }
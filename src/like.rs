@@ -0,0 +1,477 @@
+use crate::memmem::Finder;
+use alloc::borrow::Cow;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Syntax of the patterns compiled by a `LikeBuilder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Syntax {
+    /// SQL `LIKE` patterns, where `%` matches any sequence of bytes and `_`
+    /// matches any single byte.
+    Like,
+    /// Shell glob patterns, where `*` matches any sequence of bytes and `?`
+    /// matches any single byte. Character classes are not supported and `[`
+    /// matches itself.
+    Glob,
+}
+
+/// Error returned when compiling a pattern fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LikeError {
+    /// The pattern ends with an escape character.
+    TrailingEscape,
+}
+
+impl core::fmt::Display for LikeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TrailingEscape => write!(f, "pattern ends with an escape character"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LikeError {}
+
+/// Builder for configuring the compilation of `LikeMatcher` patterns.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::like::{LikeBuilder, Syntax};
+///
+/// let matcher = LikeBuilder::new()
+///     .syntax(Syntax::Glob)
+///     .ascii_case_insensitive(true)
+///     .build(b"*.tar.?z")
+///     .unwrap();
+///
+/// assert!(matcher.is_match(b"Archive.TAR.GZ"));
+/// assert!(!matcher.is_match(b"archive.tar.bz2"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LikeBuilder {
+    syntax: Syntax,
+    escape: Option<u8>,
+    ascii_case_insensitive: bool,
+}
+
+impl Default for LikeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LikeBuilder {
+    /// Creates a new builder for case sensitive SQL `LIKE` patterns escaped
+    /// with a backslash.
+    pub fn new() -> Self {
+        Self {
+            syntax: Syntax::Like,
+            escape: Some(b'\\'),
+            ascii_case_insensitive: false,
+        }
+    }
+
+    /// Sets the syntax of the patterns.
+    pub fn syntax(&mut self, syntax: Syntax) -> &mut Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Sets the escape character, which makes the following byte match itself,
+    /// or disables escaping for `None`. Defaults to a backslash.
+    pub fn escape(&mut self, escape: Option<u8>) -> &mut Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Sets whether ASCII letters match regardless of their case.
+    pub fn ascii_case_insensitive(&mut self, yes: bool) -> &mut Self {
+        self.ascii_case_insensitive = yes;
+        self
+    }
+
+    /// Compiles `pattern` with the configured settings.
+    ///
+    /// Returns an error if `pattern` ends with the escape character.
+    pub fn build(&self, pattern: &[u8]) -> Result<LikeMatcher, LikeError> {
+        let (many, one) = match self.syntax {
+            Syntax::Like => (b'%', b'_'),
+            Syntax::Glob => (b'*', b'?'),
+        };
+
+        // Parts of the pattern separated by sequence wildcards, where `None`
+        // stands for a single byte wildcard.
+        let mut parts = vec![Vec::new()];
+        let mut bytes = pattern.iter();
+        while let Some(&b) = bytes.next() {
+            let token = match b {
+                _ if Some(b) == self.escape => {
+                    Some(*bytes.next().ok_or(LikeError::TrailingEscape)?)
+                }
+                _ if b == many => {
+                    parts.push(Vec::new());
+                    continue;
+                }
+                _ if b == one => None,
+                _ => Some(b),
+            };
+            let token = match self.ascii_case_insensitive {
+                true => token.map(|b| b.to_ascii_lowercase()),
+                false => token,
+            };
+            parts.last_mut().unwrap().push(token);
+        }
+
+        let prefix = Part::new(parts.remove(0));
+        let suffix = parts.pop().map(Part::new);
+        let inner: Vec<Inner> = parts
+            .into_iter()
+            .filter(|tokens| !tokens.is_empty())
+            .map(Inner::new)
+            .collect();
+
+        let min_len = prefix.len()
+            + suffix.as_ref().map_or(0, Part::len)
+            + inner.iter().map(|inner| inner.part.len()).sum::<usize>();
+
+        Ok(LikeMatcher {
+            prefix,
+            suffix,
+            inner,
+            min_len,
+            ascii_case_insensitive: self.ascii_case_insensitive,
+        })
+    }
+}
+
+/// Sequence of bytes and single byte wildcards between sequence wildcards.
+#[derive(Clone, Debug)]
+struct Part {
+    tokens: Vec<Option<u8>>,
+    /// Bytes of the part, only compared when it contains no wildcard.
+    literal: Option<Vec<u8>>,
+}
+
+impl Part {
+    fn new(tokens: Vec<Option<u8>>) -> Self {
+        let literal = tokens.iter().copied().collect();
+        Self { tokens, literal }
+    }
+
+    fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns whether the part matches `haystack`, which has the same length.
+    #[inline]
+    fn is_match(&self, haystack: &[u8]) -> bool {
+        match &self.literal {
+            Some(literal) => haystack == &literal[..],
+            None => self
+                .tokens
+                .iter()
+                .zip(haystack)
+                .all(|(token, &b)| token.is_none() || *token == Some(b)),
+        }
+    }
+}
+
+/// Part of the pattern which is neither anchored at the start nor the end of
+/// the haystack, searched for using its longest run of bytes.
+struct Inner {
+    part: Part,
+    finder: Option<Finder<'static>>,
+    /// Offset of the run searched for within the part.
+    offset: usize,
+}
+
+impl core::fmt::Debug for Inner {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Inner")
+            .field("part", &self.part)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl Inner {
+    fn new(tokens: Vec<Option<u8>>) -> Self {
+        let mut longest = 0..0;
+        let mut start = 0;
+        for (i, token) in tokens.iter().enumerate() {
+            if token.is_none() {
+                start = i + 1;
+            } else if i + 1 - start > longest.len() {
+                longest = start..i + 1;
+            }
+        }
+
+        let run: Vec<u8> = tokens[longest.clone()].iter().flatten().copied().collect();
+        let finder = (!run.is_empty()).then(|| Finder::new(&run).into_owned());
+
+        Self {
+            part: Part::new(tokens),
+            finder,
+            offset: longest.start,
+        }
+    }
+
+    /// Returns the end of the leftmost match of the part within
+    /// `haystack[start..end]`, if any.
+    fn find(&self, haystack: &[u8], start: usize, end: usize) -> Option<usize> {
+        let len = self.part.len();
+        if end - start < len {
+            return None;
+        }
+
+        let Some(finder) = &self.finder else {
+            return Some(start + len);
+        };
+
+        // Candidates leave room for the whole part around the run.
+        let run = finder.needle().len();
+        let window = (start + self.offset)..(end - len + self.offset + run);
+        let mut from = window.start;
+        while let Some(i) = finder.find(&haystack[from..window.end]) {
+            let position = from + i - self.offset;
+            if self.part.is_match(&haystack[position..position + len]) {
+                return Some(position + len);
+            }
+            from += i + 1;
+        }
+        None
+    }
+}
+
+/// Matcher of SQL `LIKE` and shell glob patterns.
+///
+/// Patterns are compiled into checks of their parts anchored at the start and
+/// at the end of the haystack, followed by searches for the parts between
+/// sequence wildcards, in order, each using a searcher for its longest run of
+/// bytes.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::like::LikeMatcher;
+///
+/// let matcher = LikeMatcher::new(b"abc%def%g_i").unwrap();
+///
+/// assert!(matcher.is_match(b"abc-def-ghi"));
+/// assert!(matcher.is_match(b"abcdefgxi"));
+/// assert!(!matcher.is_match(b"abc-ghi-def"));
+/// ```
+#[derive(Debug)]
+pub struct LikeMatcher {
+    prefix: Part,
+    /// Part anchored at the end, if the pattern contains a sequence wildcard.
+    suffix: Option<Part>,
+    inner: Vec<Inner>,
+    min_len: usize,
+    ascii_case_insensitive: bool,
+}
+
+impl LikeMatcher {
+    /// Compiles the SQL `LIKE` `pattern`, escaped with a backslash.
+    pub fn new(pattern: &[u8]) -> Result<Self, LikeError> {
+        LikeBuilder::new().build(pattern)
+    }
+
+    /// Compiles the shell glob `pattern`, escaped with a backslash.
+    pub fn glob(pattern: &[u8]) -> Result<Self, LikeError> {
+        LikeBuilder::new().syntax(Syntax::Glob).build(pattern)
+    }
+
+    /// Returns whether the whole `haystack` matches the pattern.
+    pub fn is_match(&self, haystack: &[u8]) -> bool {
+        if haystack.len() < self.min_len {
+            return false;
+        }
+
+        let haystack = if self.ascii_case_insensitive && haystack.iter().any(u8::is_ascii_uppercase)
+        {
+            Cow::Owned(haystack.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(haystack)
+        };
+
+        let Some(suffix) = &self.suffix else {
+            return haystack.len() == self.prefix.len() && self.prefix.is_match(&haystack);
+        };
+
+        let start = self.prefix.len();
+        let end = haystack.len() - suffix.len();
+        if !self.prefix.is_match(&haystack[..start]) || !suffix.is_match(&haystack[end..]) {
+            return false;
+        }
+
+        // Matching each part as early as possible leaves the most room for the
+        // following ones.
+        let mut start = start;
+        for inner in &self.inner {
+            match inner.find(&haystack, start, end) {
+                Some(next) => start = next,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Returns the searcher for the only byte sequence to be found anywhere
+    /// within the haystack, if the pattern is of the form `%literal%`.
+    pub(crate) fn as_contains(&self) -> Option<&Finder<'static>> {
+        match &self.inner[..] {
+            [inner]
+                if self.prefix.tokens.is_empty()
+                    && self
+                        .suffix
+                        .as_ref()
+                        .is_some_and(|suffix| suffix.tokens.is_empty())
+                    && inner.part.literal.is_some()
+                    && !self.ascii_case_insensitive =>
+            {
+                inner.finder.as_ref()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LikeBuilder, LikeError, LikeMatcher, Syntax};
+
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    /// Naive backtracking implementation of `LIKE` matching.
+    fn naive(pattern: &[u8], haystack: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => haystack.is_empty(),
+            Some((b'%', rest)) => (0..=haystack.len()).any(|i| naive(rest, &haystack[i..])),
+            Some((b'_', rest)) => !haystack.is_empty() && naive(rest, &haystack[1..]),
+            Some((&b, rest)) => haystack.first() == Some(&b) && naive(rest, &haystack[1..]),
+        }
+    }
+
+    fn search(patterns: &[&[u8]], haystacks: &[&[u8]]) {
+        for &pattern in patterns {
+            let matcher = LikeMatcher::new(pattern).unwrap();
+            let glob: Vec<u8> = pattern
+                .iter()
+                .map(|&b| match b {
+                    b'%' => b'*',
+                    b'_' => b'?',
+                    _ => b,
+                })
+                .collect();
+            let glob = LikeMatcher::glob(&glob).unwrap();
+            let insensitive = LikeBuilder::new()
+                .ascii_case_insensitive(true)
+                .build(&pattern.to_ascii_uppercase())
+                .unwrap();
+
+            for &haystack in haystacks {
+                let expected = naive(pattern, haystack);
+                assert_eq!(
+                    matcher.is_match(haystack),
+                    expected,
+                    "{pattern:?} {haystack:?}"
+                );
+                assert_eq!(
+                    glob.is_match(haystack),
+                    expected,
+                    "{pattern:?} {haystack:?}"
+                );
+                assert_eq!(
+                    insensitive.is_match(haystack),
+                    naive(pattern, &haystack.to_ascii_lowercase()),
+                    "{pattern:?} {haystack:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn like_match() {
+        let patterns: [&[u8]; 16] = [
+            b"",
+            b"%",
+            b"%%",
+            b"_",
+            b"a",
+            b"a%",
+            b"%a",
+            b"%a%",
+            b"a%b",
+            b"%ab%ba%",
+            b"a_b",
+            b"%a_b%",
+            b"_%_",
+            b"%_b_%a",
+            b"abc%def%ghi",
+            b"%aa%aa%",
+        ];
+        let haystacks: [&[u8]; 14] = [
+            b"",
+            b"a",
+            b"A",
+            b"b",
+            b"ab",
+            b"aB",
+            b"ba",
+            b"aab",
+            b"abba",
+            b"aaaa",
+            b"abab",
+            b"abcdefghi",
+            b"abc-DEF-ghi",
+            b"abcghidef",
+        ];
+
+        search(&patterns, &haystacks);
+    }
+
+    #[test]
+    fn like_match_words() {
+        let haystacks: Vec<&[u8]> = WORDS.split(|&b| b == b'\n').collect();
+        let patterns: [&[u8]; 6] = [b"%ation", b"un%", b"%a_a%", b"%qu%z%", b"a%b%c%d%", b"____"];
+
+        search(&patterns, &haystacks);
+    }
+
+    #[test]
+    fn like_escape() {
+        let matcher = LikeMatcher::new(b"100\\%%").unwrap();
+        assert!(matcher.is_match(b"100% sure"));
+        assert!(!matcher.is_match(b"1000"));
+
+        let matcher = LikeMatcher::glob(b"\\*\\?*").unwrap();
+        assert!(matcher.is_match(b"*?"));
+        assert!(!matcher.is_match(b"a?"));
+
+        let matcher = LikeBuilder::new()
+            .escape(Some(b'!'))
+            .build(b"a!_%")
+            .unwrap();
+        assert!(matcher.is_match(b"a_b"));
+        assert!(!matcher.is_match(b"ab"));
+
+        let matcher = LikeBuilder::new().escape(None).build(b"\\%").unwrap();
+        assert!(matcher.is_match(b"\\abc"));
+
+        let matcher = LikeBuilder::new()
+            .syntax(Syntax::Glob)
+            .build(b"%_")
+            .unwrap();
+        assert!(matcher.is_match(b"%_"));
+        assert!(!matcher.is_match(b"ab"));
+
+        assert_eq!(
+            LikeMatcher::new(b"abc\\").unwrap_err(),
+            LikeError::TrailingEscape
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use crate::builder::candidate_searcher;
+use crate::{Needle, SubstringSearch};
+use alloc::boxed::Box;
+
+/// Searcher for the empty needle, which matches at every position.
+struct EmptySearcher;
+
+impl SubstringSearch for EmptySearcher {
+    fn needle(&self) -> &[u8] {
+        &[]
+    }
+
+    fn position(&self) -> usize {
+        0
+    }
+
+    fn search_in(&self, _: &[u8]) -> bool {
+        true
+    }
+
+    fn find(&self, _: &[u8]) -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// Selects the fastest searcher available at runtime for `needle`.
+fn best_searcher<'n, N: Needle + Send + Sync + 'n>(
+    needle: N,
+) -> Box<dyn SubstringSearch + Send + Sync + 'n> {
+    match needle.as_bytes().len() {
+        0 => Box::new(EmptySearcher),
+        _ => candidate_searcher(needle),
+    }
+}
+
+/// Single substring searcher with the same interface as
+/// `memchr::memmem::Finder`, backed by the fastest searcher available at
+/// runtime.
+pub struct Finder<'n> {
+    searcher: FinderSearcher<'n>,
+}
+
+/// Searcher of a `Finder`, borrowed from another finder by `Finder::as_ref`.
+enum FinderSearcher<'n> {
+    Owned(Box<dyn SubstringSearch + Send + Sync + 'n>),
+    Borrowed(&'n (dyn SubstringSearch + Send + Sync + 'n)),
+}
+
+impl<'n> Finder<'n> {
+    /// Creates a new finder for `needle`.
+    pub fn new<B: ?Sized + AsRef<[u8]>>(needle: &'n B) -> Finder<'n> {
+        Finder {
+            searcher: FinderSearcher::Owned(best_searcher(needle.as_ref())),
+        }
+    }
+
+    /// Returns the underlying searcher.
+    fn searcher(&self) -> &(dyn SubstringSearch + Send + Sync + 'n) {
+        match &self.searcher {
+            FinderSearcher::Owned(searcher) => &**searcher,
+            FinderSearcher::Borrowed(searcher) => *searcher,
+        }
+    }
+
+    /// Returns the position of the leftmost match of the needle within
+    /// `haystack`, if any.
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.searcher().find(haystack)
+    }
+
+    /// Returns an iterator over the positions of all non-overlapping matches of
+    /// the needle within `haystack`, from left to right.
+    pub fn find_iter<'a, 'h>(&'a self, haystack: &'h [u8]) -> FindIter<'h, 'a> {
+        FindIter {
+            matches: Matches::new(self.searcher(), haystack),
+        }
+    }
+
+    /// Converts this finder into one which owns its needle. The searcher is
+    /// built again for a copy of the needle, since it may borrow the original.
+    pub fn into_owned(self) -> Finder<'static> {
+        let needle: Box<[u8]> = self.needle().into();
+        Finder {
+            searcher: FinderSearcher::Owned(best_searcher(needle)),
+        }
+    }
+
+    /// Returns a finder which borrows its searcher from this one, without
+    /// building a new one.
+    pub fn as_ref(&self) -> Finder<'_> {
+        Finder {
+            searcher: FinderSearcher::Borrowed(self.searcher()),
+        }
+    }
+
+    /// Returns the needle searched for.
+    pub fn needle(&self) -> &[u8] {
+        self.searcher().needle()
+    }
+
+    /// Returns the approximate heap memory used by the searcher, including a
+    /// copy of the needle as made by `into_owned`. A finder borrowing its
+    /// searcher from another one does not use any.
+    #[cfg(feature = "regex-automata")]
+    pub(crate) fn memory_usage(&self) -> usize {
+        match &self.searcher {
+            FinderSearcher::Owned(searcher) => size_of_val(&**searcher) + searcher.needle().len(),
+            FinderSearcher::Borrowed(_) => 0,
+        }
+    }
+}
+
+impl SubstringSearch for Finder<'_> {
+    fn needle(&self) -> &[u8] {
+        self.searcher().needle()
+    }
+
+    fn position(&self) -> usize {
+        self.searcher().position()
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        self.searcher().search_in(haystack)
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.searcher().find(haystack)
+    }
+
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        self.searcher().search_batch_in(haystacks, matches)
+    }
+
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        self.searcher().search_offsets_in(offsets, data, matches)
+    }
+}
+
+/// Iterator over the non-overlapping matches of a `Finder`, from left to right.
+pub struct FindIter<'h, 'n> {
+    matches: Matches<'h, &'n dyn SubstringSearch>,
+}
+
+impl Iterator for FindIter<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.matches.next()
+    }
+}
+
+/// Iterator over the positions of the non-overlapping matches of any searcher
+/// within a haystack, from left to right.
+pub(crate) struct Matches<'h, S> {
+    searcher: S,
+    haystack: &'h [u8],
+    position: usize,
+}
+
+impl<'h, S: SubstringSearch> Matches<'h, S> {
+    pub(crate) fn new(searcher: S, haystack: &'h [u8]) -> Self {
+        Self {
+            searcher,
+            haystack,
+            position: 0,
+        }
+    }
+
+    pub(crate) fn needle(&self) -> &[u8] {
+        self.searcher.needle()
+    }
+}
+
+impl<S: SubstringSearch> Iterator for Matches<'_, S> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.position > self.haystack.len() {
+            return None;
+        }
+
+        match self.searcher.find(&self.haystack[self.position..]) {
+            Some(i) => {
+                let position = self.position + i;
+                // Empty needles match at every position, so always move
+                // forward by at least one byte.
+                self.position = position + self.searcher.needle().len().max(1);
+                Some(position)
+            }
+            None => {
+                self.position = self.haystack.len() + 1;
+                None
+            }
+        }
+    }
+}
+
+/// Single substring searcher with the same interface as
+/// `memchr::memmem::FinderRev`, searching from right to left.
+///
+/// This is a thin wrapper around `memchr::memmem::FinderRev` and does not use
+/// the SIMD searchers of this crate, which only search forwards. It stops at
+/// the rightmost match instead of scanning the whole haystack.
+pub struct FinderRev<'n> {
+    finder: memchr::memmem::FinderRev<'n>,
+}
+
+impl<'n> FinderRev<'n> {
+    /// Creates a new reverse finder for `needle`.
+    pub fn new<B: ?Sized + AsRef<[u8]>>(needle: &'n B) -> FinderRev<'n> {
+        FinderRev {
+            finder: memchr::memmem::FinderRev::new(needle),
+        }
+    }
+
+    /// Returns the position of the rightmost match of the needle within
+    /// `haystack`, if any.
+    pub fn rfind(&self, haystack: &[u8]) -> Option<usize> {
+        self.finder.rfind(haystack)
+    }
+
+    /// Returns an iterator over the positions of all non-overlapping matches of
+    /// the needle within `haystack`, from right to left.
+    pub fn rfind_iter<'a, 'h>(&'a self, haystack: &'h [u8]) -> FindRevIter<'h, 'a> {
+        FindRevIter {
+            matches: self.finder.rfind_iter(haystack),
+        }
+    }
+
+    /// Converts this finder into one which owns its needle.
+    pub fn into_owned(self) -> FinderRev<'static> {
+        FinderRev {
+            finder: self.finder.into_owned(),
+        }
+    }
+
+    /// Returns a finder which borrows its needle from this one.
+    pub fn as_ref(&self) -> FinderRev<'_> {
+        FinderRev {
+            finder: self.finder.as_ref(),
+        }
+    }
+
+    /// Returns the needle searched for.
+    pub fn needle(&self) -> &[u8] {
+        self.finder.needle()
+    }
+}
+
+/// Iterator over the non-overlapping matches of a `FinderRev`, from right to
+/// left.
+pub struct FindRevIter<'h, 'n> {
+    matches: memchr::memmem::FindRevIter<'h, 'n>,
+}
+
+impl Iterator for FindRevIter<'_, '_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.matches.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Finder, FinderRev};
+
+    static HAYSTACK: &[u8] = include_bytes!("../data/haystack");
+
+    /// Substring search cases from the `memchr` test suite, with the expected
+    /// positions of the leftmost and rightmost matches.
+    const SEEDS: &[(&str, &str, Option<usize>, Option<usize>)] = &[
+        ("", "", Some(0), Some(0)),
+        ("", "a", Some(0), Some(1)),
+        ("", "ab", Some(0), Some(2)),
+        ("", "abc", Some(0), Some(3)),
+        ("a", "", None, None),
+        ("a", "a", Some(0), Some(0)),
+        ("a", "aa", Some(0), Some(1)),
+        ("a", "ba", Some(1), Some(1)),
+        ("a", "bba", Some(2), Some(2)),
+        ("a", "bbba", Some(3), Some(3)),
+        ("a", "bbbab", Some(3), Some(3)),
+        ("a", "bbbabb", Some(3), Some(3)),
+        ("a", "bbbabbb", Some(3), Some(3)),
+        ("a", "bbbbbb", None, None),
+        ("ab", "", None, None),
+        ("ab", "a", None, None),
+        ("ab", "b", None, None),
+        ("ab", "ab", Some(0), Some(0)),
+        ("ab", "aab", Some(1), Some(1)),
+        ("ab", "aaab", Some(2), Some(2)),
+        ("ab", "abaab", Some(0), Some(3)),
+        ("ab", "baaab", Some(3), Some(3)),
+        ("ab", "acb", None, None),
+        ("ab", "abba", Some(0), Some(0)),
+        ("abc", "ab", None, None),
+        ("abc", "abc", Some(0), Some(0)),
+        ("abc", "abcz", Some(0), Some(0)),
+        ("abc", "abczz", Some(0), Some(0)),
+        ("abc", "zabc", Some(1), Some(1)),
+        ("abc", "zzabc", Some(2), Some(2)),
+        ("abc", "azbc", None, None),
+        ("abc", "abzc", None, None),
+        ("abczdef", "abczdefzzzzzzzzzzzzzzzzzzzz", Some(0), Some(0)),
+        ("abczdef", "zzzzzzzzzzzzzzzzzzzzabczdef", Some(20), Some(20)),
+        (
+            "xyz",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaxyz",
+            Some(32),
+            Some(32),
+        ),
+        ("\u{0}\u{15}", "\u{0}\u{15}\u{15}\u{0}", Some(0), Some(0)),
+        ("\u{0}\u{1e}", "\u{1e}\u{0}", None, None),
+    ];
+
+    /// Generates the test cases for each seed, padding the haystack at the
+    /// beginning and at the end as done by `memchr`.
+    fn cases() -> Vec<(String, String, Option<usize>, Option<usize>)> {
+        let mut cases = Vec::new();
+        for &(needle, haystack, fwd, rev) in SEEDS {
+            for pad in 0..=34 {
+                let haystack = format!("{}{}", "#".repeat(pad), haystack);
+                let (fwd, rev) = if needle.is_empty() {
+                    (Some(0), Some(haystack.len()))
+                } else {
+                    (fwd.map(|i| pad + i), rev.map(|i| pad + i))
+                };
+                cases.push((needle.to_owned(), haystack, fwd, rev));
+            }
+            for pad in 1..=34 {
+                let haystack = format!("{}{}", haystack, "#".repeat(pad));
+                let rev = if needle.is_empty() {
+                    Some(haystack.len())
+                } else {
+                    rev
+                };
+                cases.push((needle.to_owned(), haystack, fwd, rev));
+            }
+        }
+        cases
+    }
+
+    #[test]
+    fn finder_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Finder<'static>>();
+        assert_send_sync::<FinderRev<'static>>();
+    }
+
+    #[test]
+    fn finder_find() {
+        for (needle, haystack, fwd, _) in cases() {
+            let finder = Finder::new(&needle);
+            assert_eq!(
+                finder.find(haystack.as_bytes()),
+                fwd,
+                "{:?} in {:?}",
+                needle,
+                haystack,
+            );
+        }
+    }
+
+    #[test]
+    fn finder_rev_rfind() {
+        for (needle, haystack, _, rev) in cases() {
+            let finder = FinderRev::new(&needle);
+            assert_eq!(
+                finder.rfind(haystack.as_bytes()),
+                rev,
+                "{:?} in {:?}",
+                needle,
+                haystack,
+            );
+        }
+    }
+
+    #[test]
+    fn finder_find_iter() {
+        let needles = [
+            &b""[..],
+            b"a",
+            b"aa",
+            b"aaa",
+            b"the",
+            b"Lorem",
+            b"consectetur",
+        ];
+
+        for (needle, haystack, _, _) in cases() {
+            let expected = memchr::memmem::find_iter(haystack.as_bytes(), &needle);
+            let finder = Finder::new(&needle);
+            assert!(finder.find_iter(haystack.as_bytes()).eq(expected));
+        }
+
+        for needle in needles {
+            let expected = memchr::memmem::find_iter(HAYSTACK, needle);
+            let finder = Finder::new(needle);
+            assert!(finder.find_iter(HAYSTACK).eq(expected), "{:?}", needle);
+        }
+    }
+
+    #[test]
+    fn finder_rev_rfind_iter() {
+        let needles = [
+            &b""[..],
+            b"a",
+            b"aa",
+            b"aaa",
+            b"the",
+            b"Lorem",
+            b"consectetur",
+        ];
+
+        for (needle, haystack, _, _) in cases() {
+            let expected = memchr::memmem::rfind_iter(haystack.as_bytes(), &needle);
+            let finder = FinderRev::new(&needle);
+            assert!(finder.rfind_iter(haystack.as_bytes()).eq(expected));
+        }
+
+        for needle in needles {
+            let expected = memchr::memmem::rfind_iter(HAYSTACK, needle);
+            let finder = FinderRev::new(needle);
+            assert!(finder.rfind_iter(HAYSTACK).eq(expected), "{:?}", needle);
+        }
+    }
+
+    #[test]
+    fn finder_owned() {
+        let finder = {
+            let needle = b"ipsum".to_vec();
+            Finder::new(&needle).into_owned()
+        };
+        assert_eq!(finder.needle(), b"ipsum");
+        assert_eq!(finder.find(b"Lorem ipsum"), Some(6));
+
+        let borrowed = finder.as_ref();
+        assert_eq!(borrowed.needle().as_ptr(), finder.needle().as_ptr());
+        assert_eq!(borrowed.find(b"Lorem ipsum"), Some(6));
+        assert_eq!(borrowed.into_owned().find(b"Lorem ipsum"), Some(6));
+
+        let finder = {
+            let needle = b"ipsum".to_vec();
+            FinderRev::new(&needle).into_owned()
+        };
+        assert_eq!(finder.needle(), b"ipsum");
+        assert_eq!(finder.rfind(b"ipsum ipsum"), Some(6));
+
+        let borrowed = finder.as_ref();
+        assert_eq!(borrowed.needle(), b"ipsum");
+        assert_eq!(borrowed.rfind(b"ipsum ipsum"), Some(6));
+    }
+}
@@ -0,0 +1,570 @@
+use crate::builder::Backend;
+use crate::{chunk_eq_needle, NeedleWithSize, SearcherError, Vector, VectorHash};
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+use memchr::memmem::Finder;
+
+/// One of the two needles searched for by a near searcher, together with the
+/// hash used to filter candidate positions for it.
+pub(crate) struct NearFilter<'a, N: NeedleWithSize + ?Sized, V: Vector> {
+    pub(crate) needle: &'a N,
+    pub(crate) position: usize,
+    pub(crate) hash: &'a VectorHash<V>,
+}
+
+multiversion! {
+unsafe fn near_search_in_chunk<
+    A: NeedleWithSize + ?Sized,
+    B: NeedleWithSize + ?Sized,
+    V: Vector,
+    F: FnMut(usize, usize) -> bool
+>(
+    first: &NearFilter<A, V>,
+    second: &NearFilter<B, V>,
+    start: *const u8,
+    mask: u32,
+    f: &mut F,
+) -> bool {
+    // Both needles start with their first byte at the same offset, so a single
+    // load is shared by the two filters.
+    let chunk = V::load(start);
+
+    let eq_first = V::bitwise_and(
+        V::lanes_eq(first.hash.first, chunk),
+        V::lanes_eq(first.hash.last, V::load(start.add(first.position))),
+    );
+    let eq_second = V::bitwise_and(
+        V::lanes_eq(second.hash.first, chunk),
+        V::lanes_eq(second.hash.last, V::load(start.add(second.position))),
+    );
+
+    let eq_first = V::to_bitmask(eq_first) & mask;
+    let eq_second = V::to_bitmask(eq_second) & mask;
+
+    // Report matches in the order they appear in the haystack.
+    let mut eq = eq_first | eq_second;
+    while eq != 0 {
+        let lane = eq.trailing_zeros() as usize;
+        let bit = 1 << lane;
+        let candidate = start.add(lane);
+
+        if eq_first & bit != 0 && chunk_eq_needle(candidate, first.needle) && f(0, lane) {
+            return true;
+        }
+        if eq_second & bit != 0 && chunk_eq_needle(candidate, second.needle) && f(1, lane) {
+            return true;
+        }
+
+        // clear left-most bit
+        eq = eq & (eq - 1);
+    }
+
+    false
+}}
+
+// Searches for both needles in a single pass over `haystack[..end]`, calling
+// `f` with the index of the needle (`0` or `1`) and the position of each match,
+// in increasing order of position. Stops as soon as `f` returns `true`. Note
+// that `end` must be computed using the longest of the two needles.
+multiversion! {
+pub(crate) unsafe fn near_search_in<
+    A: NeedleWithSize + ?Sized,
+    B: NeedleWithSize + ?Sized,
+    V: Vector,
+    F: FnMut(usize, usize) -> bool
+>(
+    first: &NearFilter<A, V>,
+    second: &NearFilter<B, V>,
+    haystack: &[u8],
+    end: usize,
+    f: &mut F,
+) -> bool {
+    debug_assert!(haystack.len() >= first.needle.size());
+    debug_assert!(haystack.len() >= second.needle.size());
+
+    let mut offset = 0;
+    let mut chunks = haystack[..end].chunks_exact(V::LANES);
+    for chunk in &mut chunks {
+        if dispatch!(TARGET => near_search_in_chunk(
+            first,
+            second,
+            chunk.as_ptr(),
+            u32::MAX,
+            &mut |needle, lane| f(needle, offset + lane)
+        )) {
+            return true;
+        }
+        offset += V::LANES;
+    }
+
+    let remainder = chunks.remainder().len();
+    if remainder > 0 {
+        let offset = end - V::LANES;
+        let start = haystack.as_ptr().add(offset);
+        let mask = u32::MAX << (V::LANES - remainder);
+
+        if dispatch!(TARGET => near_search_in_chunk(
+            first,
+            second,
+            start,
+            mask,
+            &mut |needle, lane| f(needle, offset + lane)
+        )) {
+            return true;
+        }
+    }
+
+    false
+}}
+
+/// Scalar search for both needles at every position from `start` onwards. This
+/// covers the tail of the haystack where only the shorter needle still fits.
+pub(crate) fn near_search_tail<F: FnMut(usize, usize) -> bool>(
+    first: &[u8],
+    second: &[u8],
+    haystack: &[u8],
+    start: usize,
+    f: &mut F,
+) -> bool {
+    for position in start..haystack.len() {
+        let rest = &haystack[position..];
+        if rest.starts_with(first) && f(0, position) {
+            return true;
+        }
+        if rest.starts_with(second) && f(1, position) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Pairs up matches of the two needles, which must be pushed in increasing
+/// order of position, whenever they occur within `distance` bytes of each
+/// other.
+pub(crate) struct NearPairs {
+    distance: usize,
+    sizes: [usize; 2],
+    recent: [VecDeque<usize>; 2],
+}
+
+impl NearPairs {
+    pub(crate) fn new(distance: usize, first_size: usize, second_size: usize) -> Self {
+        Self {
+            distance,
+            sizes: [first_size, second_size],
+            recent: [VecDeque::new(), VecDeque::new()],
+        }
+    }
+
+    /// Records a match of `needle` at `position` and calls `f` with the
+    /// positions of the first and second needles for every pair it completes.
+    pub(crate) fn push<F: FnMut(usize, usize) -> bool>(
+        &mut self,
+        needle: usize,
+        position: usize,
+        f: &mut F,
+    ) -> bool {
+        let other = 1 - needle;
+        let limit = position.saturating_sub(self.distance);
+
+        // Matches of either needle ending more than `distance` bytes before
+        // this one can no longer be paired with any later match.
+        for (recent, &size) in self.recent.iter_mut().zip(&self.sizes) {
+            while let Some(&start) = recent.front() {
+                if start + size >= limit {
+                    break;
+                }
+                recent.pop_front();
+            }
+        }
+
+        let recent = &self.recent[other];
+        for &start in recent.iter() {
+            let stop = if needle == 0 {
+                f(position, start)
+            } else {
+                f(start, position)
+            };
+            if stop {
+                return true;
+            }
+        }
+
+        self.recent[needle].push_back(position);
+        false
+    }
+}
+
+/// Hashes of both needles of a `NearSearcher` for the vector type of its
+/// backend.
+struct NearHashes<V: Vector> {
+    first: VectorHash<V>,
+    second: VectorHash<V>,
+}
+
+impl<V: Vector> NearHashes<V> {
+    /// Creates the hashes filtering on the first and last bytes of each needle.
+    ///
+    /// # Safety
+    ///
+    /// The target features required by `V` must be available.
+    unsafe fn new(first: &[u8], second: &[u8]) -> Self {
+        Self {
+            first: VectorHash::new(first[0], first[first.len() - 1]),
+            second: VectorHash::new(second[0], second[second.len() - 1]),
+        }
+    }
+}
+
+/// Implementation used by a `NearSearcher`, selected at runtime.
+#[allow(clippy::large_enum_variant)]
+enum NearBackend {
+    #[cfg(target_arch = "x86_64")]
+    Avx2(NearHashes<core::arch::x86_64::__m256i>),
+    #[cfg(target_arch = "aarch64")]
+    Neon(NearHashes<core::arch::aarch64::uint8x16_t>),
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    Wasm32(NearHashes<core::arch::wasm32::v128>),
+    #[cfg(feature = "stdsimd")]
+    StdSimd(NearHashes<core::simd::Simd<u8, 32>>),
+    Scalar([Finder<'static>; 2]),
+}
+
+/// Version of `near_search_in` enabling the target features of a backend.
+type NearSearchFn<V, F> =
+    unsafe fn(&NearFilter<[u8], V>, &NearFilter<[u8], V>, &[u8], usize, &mut F) -> bool;
+
+/// Proximity searcher reporting every pair of matches of two needles which
+/// occur within a given number of bytes of each other, in either order.
+///
+/// Both needles are searched for in a single pass over the haystack, using the
+/// fastest backend available at runtime with the two hashes applied to the
+/// same loaded chunks. The distance between two matches is the number of bytes
+/// separating the end of the earlier match from the start of the later one, or
+/// zero if they overlap.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::near::NearSearcher;
+///
+/// let searcher = NearSearcher::new(b"union", b"select", 5);
+///
+/// assert!(searcher.search_in(b"1 union all select 2"));
+/// assert_eq!(searcher.find_pairs_in(b"select union"), [(7, 0)]);
+/// ```
+pub struct NearSearcher {
+    first: Box<[u8]>,
+    second: Box<[u8]>,
+    distance: usize,
+    backend: NearBackend,
+}
+
+impl NearSearcher {
+    /// Creates a new searcher for matches of `first` and `second` at most
+    /// `distance` bytes apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either needle is empty.
+    pub fn new(first: &[u8], second: &[u8], distance: usize) -> Self {
+        Self::with_backend(first, second, distance, Backend::detect())
+    }
+
+    /// Same as `new` but uses `backend`, falling back to the scalar backend if
+    /// it has no near search implementation or is not available.
+    fn with_backend(first: &[u8], second: &[u8], distance: usize, backend: Backend) -> Self {
+        if first.is_empty() || second.is_empty() {
+            panic!("{}", SearcherError::EmptyNeedle);
+        }
+
+        // Only backends available at runtime are selected by `detect`.
+        let backend = match backend {
+            #[cfg(target_arch = "x86_64")]
+            Backend::Avx2 if Backend::detect() == Backend::Avx2 => {
+                NearBackend::Avx2(unsafe { NearHashes::new(first, second) })
+            }
+            #[cfg(target_arch = "aarch64")]
+            Backend::Neon if Backend::detect() == Backend::Neon => {
+                NearBackend::Neon(unsafe { NearHashes::new(first, second) })
+            }
+            #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+            Backend::Wasm32 => NearBackend::Wasm32(unsafe { NearHashes::new(first, second) }),
+            #[cfg(feature = "stdsimd")]
+            Backend::StdSimd => NearBackend::StdSimd(unsafe { NearHashes::new(first, second) }),
+            _ => NearBackend::Scalar([
+                Finder::new(first).into_owned(),
+                Finder::new(second).into_owned(),
+            ]),
+        };
+
+        Self {
+            first: first.into(),
+            second: second.into(),
+            distance,
+            backend,
+        }
+    }
+
+    /// Searches for both needles using `hashes` and `search`, the version of
+    /// `near_search_in` for the vector type `V` of the backend. Haystacks too
+    /// short to fill one vector are searched using `near_search_tail`.
+    ///
+    /// # Safety
+    ///
+    /// The target features required by `V` must be available.
+    #[inline(always)]
+    unsafe fn vector_search_in<V: Vector, F: FnMut(usize, usize) -> bool>(
+        &self,
+        hashes: &NearHashes<V>,
+        search: NearSearchFn<V, F>,
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        let size = self.first.len().max(self.second.len());
+        if haystack.len() < size + V::LANES - 1 {
+            return near_search_tail(&self.first, &self.second, haystack, 0, f);
+        }
+
+        let first = NearFilter {
+            needle: &*self.first,
+            position: self.first.len() - 1,
+            hash: &hashes.first,
+        };
+        let second = NearFilter {
+            needle: &*self.second,
+            position: self.second.len() - 1,
+            hash: &hashes.second,
+        };
+
+        // Every chunk loaded by the search starts before `end`, so that it
+        // fits within the haystack even when offset by the longer needle.
+        let end = haystack.len() - size + 1;
+        search(&first, &second, haystack, end, f)
+            || near_search_tail(&self.first, &self.second, haystack, end, f)
+    }
+
+    /// Merges the matches of both needles found by `finders`.
+    fn scalar_search_in<F: FnMut(usize, usize) -> bool>(
+        finders: &[Finder<'static>; 2],
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        let find = |needle: usize, start: usize| {
+            finders[needle].find(&haystack[start..]).map(|i| start + i)
+        };
+
+        let mut next = [find(0, 0), find(1, 0)];
+        loop {
+            // The first needle is reported first at the same position.
+            let needle = match next {
+                [Some(first), Some(second)] => usize::from(second < first),
+                [Some(_), None] => 0,
+                [None, Some(_)] => 1,
+                [None, None] => return false,
+            };
+
+            let position = next[needle].unwrap();
+            if f(needle, position) {
+                return true;
+            }
+            next[needle] = find(needle, position + 1);
+        }
+    }
+
+    /// Calls `f` with the index of the needle (`0` or `1`) and the position of
+    /// every match of either needle, in increasing order of position, stopping
+    /// as soon as `f` returns `true`.
+    fn search_matches_in<F: FnMut(usize, usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        // The backend was only selected if its target features are available.
+        match &self.backend {
+            #[cfg(target_arch = "x86_64")]
+            NearBackend::Avx2(hashes) => unsafe {
+                self.vector_search_in(hashes, near_search_in_avx2_version, haystack, f)
+            },
+            #[cfg(target_arch = "aarch64")]
+            NearBackend::Neon(hashes) => unsafe {
+                self.vector_search_in(hashes, near_search_in_neon_version, haystack, f)
+            },
+            #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+            NearBackend::Wasm32(hashes) => unsafe {
+                self.vector_search_in(hashes, near_search_in_simd128_version, haystack, f)
+            },
+            #[cfg(feature = "stdsimd")]
+            NearBackend::StdSimd(hashes) => unsafe {
+                self.vector_search_in(hashes, near_search_in_default_version, haystack, f)
+            },
+            NearBackend::Scalar(finders) => Self::scalar_search_in(finders, haystack, f),
+        }
+    }
+
+    /// Calls `f` with the positions of the first and second needles for every
+    /// pair of matches within `distance` bytes of each other, stopping as soon
+    /// as `f` returns `true`. Pairs are reported as soon as the later of their
+    /// two matches is found.
+    pub fn for_each_pair_in<F: FnMut(usize, usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut f: F,
+    ) -> bool {
+        let mut pairs = NearPairs::new(self.distance, self.first.len(), self.second.len());
+        self.search_matches_in(haystack, &mut |needle, position| {
+            pairs.push(needle, position, &mut f)
+        })
+    }
+
+    /// Returns the positions of the first and second needles for every pair of
+    /// matches within `distance` bytes of each other.
+    pub fn find_pairs_in(&self, haystack: &[u8]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        self.for_each_pair_in(haystack, |first, second| {
+            pairs.push((first, second));
+            false
+        });
+        pairs
+    }
+
+    /// Returns whether both needles occur within `distance` bytes of each
+    /// other in `haystack`.
+    pub fn search_in(&self, haystack: &[u8]) -> bool {
+        self.for_each_pair_in(haystack, |_, _| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NearPairs, NearSearcher};
+    use crate::builder::Backend;
+
+    /// Naive implementation of near search returning every pair of matches in
+    /// the order they are reported by the near searchers.
+    fn near_pairs(
+        haystack: &[u8],
+        first: &[u8],
+        second: &[u8],
+        distance: usize,
+    ) -> Vec<(usize, usize)> {
+        let positions = |needle: &[u8]| {
+            (0..haystack.len())
+                .filter(|&i| haystack[i..].starts_with(needle))
+                .collect::<Vec<_>>()
+        };
+        let (firsts, seconds) = (positions(first), positions(second));
+
+        let mut pairs = Vec::new();
+        for &i in &firsts {
+            for &j in &seconds {
+                let gap = if i <= j {
+                    j.saturating_sub(i + first.len())
+                } else {
+                    i.saturating_sub(j + second.len())
+                };
+                if gap <= distance {
+                    pairs.push((i, j));
+                }
+            }
+        }
+
+        // Pairs are reported once the later of the two matches is found, with
+        // the first needle reported before the second at the same position.
+        pairs.sort_by_key(|&(i, j)| if i <= j { (j, 1, i) } else { (i, 0, j) });
+        pairs
+    }
+
+    fn search(
+        backend: Backend,
+        haystack: &[u8],
+        first: &'static [u8],
+        second: &'static [u8],
+        distance: usize,
+    ) {
+        let searcher = NearSearcher::with_backend(first, second, distance, backend);
+
+        for end in 0..=haystack.len() {
+            let haystack = &haystack[..end];
+            assert_eq!(
+                searcher.find_pairs_in(haystack),
+                near_pairs(haystack, first, second, distance),
+                "{:?} near {:?} within {} in {:?}",
+                first,
+                second,
+                distance,
+                haystack,
+            );
+        }
+    }
+
+    fn near_search(backend: Backend) {
+        search(backend, b"foo bar", b"foo", b"bar", 0);
+
+        search(backend, b"foo bar", b"foo", b"bar", 1);
+
+        search(backend, b"bar foo", b"foo", b"bar", 1);
+
+        search(backend, b"foobar", b"foo", b"bar", 0);
+
+        search(backend, b"foobar", b"ob", b"ba", 0);
+
+        search(backend, b"ab", b"abc", b"b", 10);
+
+        search(backend, b"union all select", b"union", b"select", 4);
+
+        search(backend, b"union all select", b"union", b"select", 5);
+
+        search(
+            backend,
+            b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            b"x",
+            b"xx",
+            2,
+        );
+
+        search(backend, &b"ab".repeat(100), b"ab", b"zzz", 3);
+
+        search(
+            backend,
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"consectetur",
+            b"t",
+            8,
+        );
+
+        search(
+            backend,
+            b"Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+            b"or",
+            b"Lorem ipsum dolor sit amet",
+            40,
+        );
+    }
+
+    #[test]
+    fn near_search_detected() {
+        near_search(Backend::detect());
+    }
+
+    #[test]
+    fn near_search_scalar() {
+        near_search(Backend::Scalar);
+    }
+
+    #[test]
+    #[should_panic(expected = "needle is empty")]
+    fn near_search_empty_needle() {
+        NearSearcher::new(b"", b"bar", 1);
+    }
+
+    #[test]
+    fn near_pairs_bounded() {
+        let mut pairs = NearPairs::new(4, 3, 3);
+        for position in (0..100_000).step_by(3) {
+            assert!(!pairs.push(0, position, &mut |_, _| unreachable!()));
+            assert!(pairs.recent[0].len() <= 3);
+        }
+        assert!(pairs.recent[1].is_empty());
+    }
+}
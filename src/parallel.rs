@@ -0,0 +1,357 @@
+use crate::memmem::Matches;
+use crate::SubstringSearch;
+use alloc::vec::{self, Vec};
+use core::iter::Flatten;
+use rayon::prelude::*;
+
+/// Bounds of the length of the chunks searched in parallel. Chunks are small
+/// enough for a chunk to be searched again quickly when a match straddles its
+/// start, and large enough for the overhead of scheduling them to be
+/// negligible.
+const MIN_CHUNK_LEN: usize = 1 << 16;
+const MAX_CHUNK_LEN: usize = 1 << 24;
+
+/// Returns the length of the chunks used to search `haystack`, giving each
+/// thread several chunks to balance the load.
+fn chunk_len(haystack: &[u8]) -> usize {
+    (haystack.len() / (4 * rayon::current_num_threads())).clamp(MIN_CHUNK_LEN, MAX_CHUNK_LEN)
+}
+
+/// Returns the range of `haystack` searched for the matches starting within
+/// the `index`th chunk, which overlaps the next chunk by `size - 1` bytes so
+/// that matches straddling chunks are found.
+fn chunk(haystack: &[u8], chunk_len: usize, size: usize, index: usize) -> (usize, usize) {
+    let start = index * chunk_len;
+    let end = start
+        .saturating_add(chunk_len)
+        .saturating_add(size - 1)
+        .min(haystack.len());
+    (start, end)
+}
+
+/// Maximum number of positions of matches kept from the start of each chunk to
+/// resynchronize it with the previous one.
+const HEAD_LEN: usize = 64;
+
+/// Collection of the matches found within a range of the haystack.
+trait Collect: Default + Extend<usize> + Send {
+    /// Removes the first `n` matches.
+    fn remove_first(&mut self, n: usize);
+}
+
+impl Collect for Vec<usize> {
+    fn remove_first(&mut self, n: usize) {
+        self.drain(..n);
+    }
+}
+
+/// Non-overlapping matches found within a range of the haystack.
+struct RangeMatches<T> {
+    found: T,
+    // Positions of the first `HEAD_LEN` matches at most.
+    head: Vec<usize>,
+    next: usize,
+}
+
+/// Searches `haystack[start..end]` for non-overlapping matches, collecting
+/// them into `T`.
+fn search_range<S, T>(searcher: &S, haystack: &[u8], start: usize, end: usize) -> RangeMatches<T>
+where
+    S: ?Sized + SubstringSearch,
+    T: Collect,
+{
+    let size = searcher.needle().len();
+    let mut head = Vec::new();
+    let mut next = start;
+
+    let mut found = T::default();
+    found.extend(Matches::new(searcher, &haystack[start..end]).map(|i| {
+        let position = start + i;
+        if head.len() < HEAD_LEN {
+            head.push(position);
+        }
+        next = position + size;
+        position
+    }));
+
+    RangeMatches { found, head, next }
+}
+
+/// Fixes the matches of a chunk ending at `end` whose first match starts before
+/// `cursor`, the end of the last match of the previous chunk, by appending them
+/// to `found`. Returns the end of the last match.
+///
+/// The chunk is searched again from `cursor` only until a match coincides with
+/// a precomputed one, since the precomputed matches which follow are then the
+/// same as when searching sequentially.
+fn resync<S, T>(
+    searcher: &S,
+    haystack: &[u8],
+    mut range: RangeMatches<T>,
+    mut cursor: usize,
+    end: usize,
+    found: &mut Vec<T>,
+) -> usize
+where
+    S: ?Sized + SubstringSearch,
+    T: Collect,
+{
+    let size = searcher.needle().len();
+    let mut resynced = T::default();
+    let mut skipped = 0;
+
+    loop {
+        // Precomputed matches starting before the cursor are discarded.
+        while range.head.get(skipped).is_some_and(|&first| first < cursor) {
+            skipped += 1;
+        }
+
+        // Without any known precomputed match left, which happens when none
+        // coincides with the first `HEAD_LEN` ones, the rest of the chunk is
+        // searched again.
+        let Some(&first) = range.head.get(skipped) else {
+            let rest = search_range::<_, T>(searcher, haystack, cursor.min(end), end);
+            found.push(resynced);
+            found.push(rest.found);
+            return rest.next.max(cursor);
+        };
+
+        // The precomputed match fits within the chunk, so there is a match
+        // starting at or before it.
+        let position = cursor + searcher.find(&haystack[cursor..first + size]).unwrap();
+        if position == first {
+            range.found.remove_first(skipped);
+            found.push(resynced);
+            found.push(range.found);
+            return range.next;
+        }
+
+        resynced.extend([position]);
+        cursor = position + size;
+    }
+}
+
+/// Searches the chunks of `haystack` in parallel, then fixes the chunks whose
+/// first match overlaps the last match of the previous chunk using `resync`,
+/// so that matches are the same as when searching sequentially.
+fn par_search<S, T>(searcher: &S, haystack: &[u8], chunk_len: usize) -> Vec<T>
+where
+    S: ?Sized + SubstringSearch + Sync,
+    T: Collect,
+{
+    let size = searcher.needle().len();
+    debug_assert!(size > 0);
+
+    let chunks = haystack.len().div_ceil(chunk_len);
+    let ranges: Vec<RangeMatches<T>> = (0..chunks)
+        .into_par_iter()
+        .map(|index| {
+            let (start, end) = chunk(haystack, chunk_len, size, index);
+            search_range(searcher, haystack, start, end)
+        })
+        .collect();
+
+    let mut next = 0;
+    let mut found = Vec::with_capacity(chunks);
+    for (index, range) in ranges.into_iter().enumerate() {
+        if range.head.first().is_some_and(|&first| first < next) {
+            let (_, end) = chunk(haystack, chunk_len, size, index);
+            next = resync(searcher, haystack, range, next, end, &mut found);
+        } else {
+            next = next.max(range.next);
+            found.push(range.found);
+        }
+    }
+    found
+}
+
+/// Counter of matches, collecting them without storing them.
+#[derive(Default)]
+struct Count(usize);
+
+impl Extend<usize> for Count {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        self.0 += iter.into_iter().count();
+    }
+}
+
+impl Collect for Count {
+    fn remove_first(&mut self, n: usize) {
+        self.0 -= n;
+    }
+}
+
+fn par_find_with<S: ?Sized + SubstringSearch + Sync>(
+    searcher: &S,
+    haystack: &[u8],
+    chunk_len: usize,
+) -> Option<usize> {
+    let size = searcher.needle().len();
+    if size == 0 {
+        return Some(0);
+    }
+
+    let chunks = haystack.len().div_ceil(chunk_len);
+    (0..chunks).into_par_iter().find_map_first(|index| {
+        let (start, end) = chunk(haystack, chunk_len, size, index);
+        searcher.find(&haystack[start..end]).map(|i| start + i)
+    })
+}
+
+fn par_find_iter_with<S: ?Sized + SubstringSearch + Sync>(
+    searcher: &S,
+    haystack: &[u8],
+    chunk_len: usize,
+) -> ParFindIter {
+    let chunks = match searcher.needle().len() {
+        0 => vec![(0..=haystack.len()).collect()],
+        _ => par_search(searcher, haystack, chunk_len),
+    };
+
+    ParFindIter {
+        positions: chunks.into_iter().flatten(),
+    }
+}
+
+fn par_count_with<S: ?Sized + SubstringSearch + Sync>(
+    searcher: &S,
+    haystack: &[u8],
+    chunk_len: usize,
+) -> usize {
+    match searcher.needle().len() {
+        0 => haystack.len() + 1,
+        _ => par_search::<_, Count>(searcher, haystack, chunk_len)
+            .into_iter()
+            .map(|count| count.0)
+            .sum(),
+    }
+}
+
+/// Returns the position of the leftmost match of `searcher` within `haystack`,
+/// if any, searching chunks of the haystack in parallel using `rayon`.
+///
+/// Chunks overlap by the length of the needle minus one byte so that matches
+/// straddling chunks are found, and the leftmost match is reported even when
+/// later chunks are searched first.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::{memmem::Finder, parallel::par_find};
+///
+/// let haystack = [&b"a".repeat(1 << 20)[..], b"needle"].concat();
+///
+/// assert_eq!(par_find(&Finder::new("needle"), &haystack), Some(1 << 20));
+/// ```
+pub fn par_find<S: ?Sized + SubstringSearch + Sync>(
+    searcher: &S,
+    haystack: &[u8],
+) -> Option<usize> {
+    par_find_with(searcher, haystack, chunk_len(haystack))
+}
+
+/// Returns an iterator over the positions of the non-overlapping matches of
+/// `searcher` within `haystack`, from left to right, with the same semantics
+/// as `memmem::Finder::find_iter`. Chunks of the haystack are searched in
+/// parallel using `rayon` before the iterator is returned, so the positions of
+/// all the matches are stored in memory until they are iterated over.
+pub fn par_find_iter<S: ?Sized + SubstringSearch + Sync>(
+    searcher: &S,
+    haystack: &[u8],
+) -> ParFindIter {
+    par_find_iter_with(searcher, haystack, chunk_len(haystack))
+}
+
+/// Returns the number of non-overlapping matches of `searcher` within
+/// `haystack`, searching chunks of the haystack in parallel using `rayon`
+/// without storing the positions of the matches.
+pub fn par_count<S: ?Sized + SubstringSearch + Sync>(searcher: &S, haystack: &[u8]) -> usize {
+    par_count_with(searcher, haystack, chunk_len(haystack))
+}
+
+/// Iterator over the positions of the non-overlapping matches found by
+/// `par_find_iter`, from left to right.
+pub struct ParFindIter {
+    positions: Flatten<vec::IntoIter<Vec<usize>>>,
+}
+
+impl Iterator for ParFindIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.positions.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        par_count, par_count_with, par_find, par_find_iter, par_find_iter_with, par_find_with,
+    };
+    use crate::memmem::Finder;
+
+    static I386: &[u8] = include_bytes!("../data/i386.txt");
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    fn search(haystack: &[u8], needle: &[u8]) {
+        let finder = Finder::new(needle);
+        let expected: Vec<usize> = memchr::memmem::find_iter(haystack, needle).collect();
+
+        for chunk_len in [1, 2, 3, 7, 64, 301, 1000, usize::MAX] {
+            assert_eq!(
+                par_find_with(&finder, haystack, chunk_len),
+                expected.first().copied(),
+                "{needle:?} {chunk_len}"
+            );
+            assert!(
+                par_find_iter_with(&finder, haystack, chunk_len).eq(expected.iter().copied()),
+                "{needle:?} {chunk_len}"
+            );
+            assert_eq!(
+                par_count_with(&finder, haystack, chunk_len),
+                expected.len(),
+                "{needle:?} {chunk_len}"
+            );
+        }
+
+        assert_eq!(par_find(&finder, haystack), expected.first().copied());
+        assert!(par_find_iter(&finder, haystack).eq(expected.iter().copied()));
+        assert_eq!(par_count(&finder, haystack), expected.len());
+    }
+
+    #[test]
+    fn parallel_search() {
+        let haystacks: [&[u8]; 6] = [b"", b"a", b"aaaaaaa", b"abababab", b"aabaabaab", b"xyzxyz"];
+        let needles: [&[u8]; 7] = [b"", b"a", b"aa", b"aaa", b"aba", b"abaab", b"zzz"];
+
+        for haystack in haystacks {
+            for needle in needles {
+                search(haystack, needle);
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_search_periodic() {
+        // Chunks of odd length never resynchronize with the previous one.
+        let haystack = b"a".repeat(1000);
+        for needle in [&b"aa"[..], b"aaa"] {
+            search(&haystack, needle);
+        }
+    }
+
+    #[test]
+    fn parallel_search_words() {
+        for needle in [&b"\n"[..], b"ation", b"ing\n", b"zzz"] {
+            search(WORDS, needle);
+        }
+    }
+
+    #[test]
+    fn parallel_search_i386() {
+        let haystack = I386.repeat(4);
+        for needle in [&b"instruction"[..], b"  ", b"Intel Architecture"] {
+            search(&haystack, needle);
+        }
+    }
+}
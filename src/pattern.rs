@@ -0,0 +1,307 @@
+use crate::memmem::{Finder, FinderRev};
+use core::str::pattern::{Pattern, ReverseSearcher, SearchStep, Searcher};
+
+/// String searcher implementing the standard library `Pattern` trait, so that
+/// methods such as `str::find`, `str::split`, `str::matches` or `str::replace`
+/// use the fastest searcher available at runtime.
+///
+/// # Example
+///
+/// ```
+/// #![feature(pattern)]
+///
+/// use sliceslice::pattern::StrFinder;
+///
+/// let finder = StrFinder::new("ipsum");
+///
+/// assert_eq!("Lorem ipsum dolor".find(&finder), Some(6));
+///
+/// assert_eq!("Lorem ipsum dolor".replace(&finder, "-"), "Lorem - dolor");
+/// ```
+pub struct StrFinder<'n> {
+    needle: &'n str,
+    finder: Finder<'n>,
+    finder_rev: FinderRev<'n>,
+}
+
+impl<'n> StrFinder<'n> {
+    /// Creates a new searcher for `needle`.
+    pub fn new(needle: &'n str) -> Self {
+        Self {
+            needle,
+            finder: Finder::new(needle),
+            finder_rev: FinderRev::new(needle),
+        }
+    }
+
+    /// Returns the needle searched for.
+    pub fn needle(&self) -> &'n str {
+        self.needle
+    }
+}
+
+impl<'b> Pattern for &'b StrFinder<'_> {
+    type Searcher<'a> = StrFinderSearcher<'a, 'b>;
+
+    fn into_searcher(self, haystack: &str) -> StrFinderSearcher<'_, 'b> {
+        StrFinderSearcher {
+            haystack,
+            finder: &self.finder,
+            finder_rev: &self.finder_rev,
+            size: self.needle.len(),
+            position: 0,
+            end: haystack.len(),
+            is_match_fw: true,
+            is_match_bw: true,
+            finished_fw: false,
+            finished_bw: false,
+        }
+    }
+
+    fn is_contained_in(self, haystack: &str) -> bool {
+        self.finder.find(haystack.as_bytes()).is_some()
+    }
+}
+
+/// Associated searcher for `StrFinder`, returned by `Pattern::into_searcher`.
+pub struct StrFinderSearcher<'a, 'b> {
+    haystack: &'a str,
+    finder: &'b Finder<'b>,
+    finder_rev: &'b FinderRev<'b>,
+    size: usize,
+    // Start of the part of the haystack not yet searched forwards.
+    position: usize,
+    // End of the part of the haystack not yet searched backwards.
+    end: usize,
+    // The empty needle alternates between matches and rejected characters.
+    is_match_fw: bool,
+    is_match_bw: bool,
+    finished_fw: bool,
+    finished_bw: bool,
+}
+
+impl StrFinderSearcher<'_, '_> {
+    fn next_empty(&mut self) -> SearchStep {
+        if self.finished_fw {
+            return SearchStep::Done;
+        }
+
+        let position = self.position;
+        self.is_match_fw = !self.is_match_fw;
+        if !self.is_match_fw {
+            return SearchStep::Match(position, position);
+        }
+
+        match self.haystack[position..].chars().next() {
+            Some(c) => {
+                self.position += c.len_utf8();
+                SearchStep::Reject(position, self.position)
+            }
+            None => {
+                self.finished_fw = true;
+                SearchStep::Done
+            }
+        }
+    }
+
+    fn next_back_empty(&mut self) -> SearchStep {
+        if self.finished_bw {
+            return SearchStep::Done;
+        }
+
+        let end = self.end;
+        self.is_match_bw = !self.is_match_bw;
+        if !self.is_match_bw {
+            return SearchStep::Match(end, end);
+        }
+
+        match self.haystack[..end].chars().next_back() {
+            Some(c) => {
+                self.end -= c.len_utf8();
+                SearchStep::Reject(self.end, end)
+            }
+            None => {
+                self.finished_bw = true;
+                SearchStep::Done
+            }
+        }
+    }
+}
+
+// Matches of a non-empty needle always start and end on character boundaries,
+// since both the needle and the haystack are valid UTF-8.
+unsafe impl<'a> Searcher<'a> for StrFinderSearcher<'a, '_> {
+    fn haystack(&self) -> &'a str {
+        self.haystack
+    }
+
+    fn next(&mut self) -> SearchStep {
+        if self.size == 0 {
+            return self.next_empty();
+        }
+
+        let position = self.position;
+        let length = self.haystack.len();
+        match self.finder.find(&self.haystack.as_bytes()[position..]) {
+            Some(0) => {
+                self.position += self.size;
+                SearchStep::Match(position, self.position)
+            }
+            Some(i) => {
+                self.position += i;
+                SearchStep::Reject(position, self.position)
+            }
+            None if position < length => {
+                self.position = length;
+                SearchStep::Reject(position, length)
+            }
+            None => SearchStep::Done,
+        }
+    }
+
+    fn next_match(&mut self) -> Option<(usize, usize)> {
+        if self.size == 0 {
+            loop {
+                match self.next_empty() {
+                    SearchStep::Match(start, end) => return Some((start, end)),
+                    SearchStep::Reject(..) => {}
+                    SearchStep::Done => return None,
+                }
+            }
+        }
+
+        match self.finder.find(&self.haystack.as_bytes()[self.position..]) {
+            Some(i) => {
+                let start = self.position + i;
+                self.position = start + self.size;
+                Some((start, self.position))
+            }
+            None => {
+                self.position = self.haystack.len();
+                None
+            }
+        }
+    }
+}
+
+unsafe impl<'a> ReverseSearcher<'a> for StrFinderSearcher<'a, '_> {
+    fn next_back(&mut self) -> SearchStep {
+        if self.size == 0 {
+            return self.next_back_empty();
+        }
+
+        let end = self.end;
+        let size = self.size;
+        match self.finder_rev.rfind(&self.haystack.as_bytes()[..end]) {
+            Some(start) if start + size == end => {
+                self.end = start;
+                SearchStep::Match(start, end)
+            }
+            Some(start) => {
+                self.end = start + size;
+                SearchStep::Reject(self.end, end)
+            }
+            None if end > 0 => {
+                self.end = 0;
+                SearchStep::Reject(0, end)
+            }
+            None => SearchStep::Done,
+        }
+    }
+
+    fn next_match_back(&mut self) -> Option<(usize, usize)> {
+        if self.size == 0 {
+            loop {
+                match self.next_back_empty() {
+                    SearchStep::Match(start, end) => return Some((start, end)),
+                    SearchStep::Reject(..) => {}
+                    SearchStep::Done => return None,
+                }
+            }
+        }
+
+        match self.finder_rev.rfind(&self.haystack.as_bytes()[..self.end]) {
+            Some(start) => {
+                let end = start + self.size;
+                self.end = start;
+                Some((start, end))
+            }
+            None => {
+                self.end = 0;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StrFinder;
+
+    static WORDS: &str = include_str!("../data/words.txt");
+
+    fn search(haystack: &str, needle: &str) {
+        let finder = StrFinder::new(needle);
+
+        assert_eq!(haystack.contains(&finder), haystack.contains(needle));
+        assert_eq!(haystack.find(&finder), haystack.find(needle));
+        assert_eq!(haystack.rfind(&finder), haystack.rfind(needle));
+        assert_eq!(haystack.starts_with(&finder), haystack.starts_with(needle));
+        assert_eq!(haystack.ends_with(&finder), haystack.ends_with(needle));
+
+        assert!(haystack.split(&finder).eq(haystack.split(needle)));
+        assert!(haystack.rsplit(&finder).eq(haystack.rsplit(needle)));
+        assert!(haystack.splitn(3, &finder).eq(haystack.splitn(3, needle)));
+        assert!(haystack
+            .split_terminator(&finder)
+            .eq(haystack.split_terminator(needle)));
+        assert!(haystack.matches(&finder).eq(haystack.matches(needle)));
+        assert!(haystack.rmatches(&finder).eq(haystack.rmatches(needle)));
+        assert!(haystack
+            .match_indices(&finder)
+            .eq(haystack.match_indices(needle)));
+        assert!(haystack
+            .rmatch_indices(&finder)
+            .eq(haystack.rmatch_indices(needle)));
+
+        assert_eq!(
+            haystack.replace(&finder, "-"),
+            haystack.replace(needle, "-")
+        );
+        assert_eq!(
+            haystack.trim_start_matches(&finder),
+            haystack.trim_start_matches(needle)
+        );
+        assert_eq!(
+            haystack.trim_end_matches(&finder),
+            haystack.trim_end_matches(needle)
+        );
+    }
+
+    #[test]
+    fn pattern_search() {
+        let haystacks = [
+            "",
+            "a",
+            "aaa",
+            "aaaaa",
+            "abab",
+            "ééaéé",
+            "Lorem ipsum dolor sit amet, consectetur adipiscing elit. Maecenas commodo posuere orci a consectetur. Ut mattis turpis ut auctor consequat",
+        ];
+        let needles = ["", "a", "aa", "ab", "é", "éa", "ipsum", "consectetur"];
+
+        for haystack in haystacks {
+            for needle in needles {
+                search(haystack, needle);
+            }
+        }
+    }
+
+    #[test]
+    fn pattern_search_words() {
+        for needle in ["\n", "e", "ing", "ation\n", "zzz"] {
+            search(WORDS, needle);
+        }
+    }
+}
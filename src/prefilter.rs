@@ -0,0 +1,216 @@
+use crate::memmem::Finder;
+use regex_automata::Span;
+use regex_syntax::hir::literal::Extractor;
+
+/// Prefilter reporting the positions of a literal which every match of a
+/// regular expression must start with, found using the fastest searcher
+/// available at runtime.
+///
+/// Its methods mirror those of `regex_automata::util::prefilter::Prefilter`.
+/// It cannot be converted into one, since `regex-automata` 0.4 keeps the trait
+/// implemented by prefilters crate-private. Instead, a regular expression built
+/// without its own prefilter is searched from each occurrence of the literal,
+/// which finds the leftmost match since no match can start before it.
+///
+/// # Example
+///
+/// ```
+/// use regex_automata::{meta::Regex, Input, Span};
+/// use sliceslice::prefilter::LiteralPrefilter;
+///
+/// let pattern = r"password=\w+";
+/// let prefilter = LiteralPrefilter::from_pattern(pattern).unwrap();
+/// let regex = Regex::builder()
+///     .configure(Regex::config().auto_prefilter(false))
+///     .build(pattern)
+///     .unwrap();
+///
+/// let haystack = b"user=foo password=bar";
+/// let candidate = prefilter.find(haystack, Span::from(0..haystack.len())).unwrap();
+/// let found = regex.search(&Input::new(haystack).range(candidate.start..));
+/// assert_eq!(found.map(|m| m.range()), Some(9..21));
+/// ```
+pub struct LiteralPrefilter {
+    finder: Finder<'static>,
+}
+
+impl LiteralPrefilter {
+    /// Creates a new prefilter for `literal`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `literal` is empty.
+    pub fn new<B: AsRef<[u8]>>(literal: B) -> Self {
+        let literal = literal.as_ref();
+        assert!(!literal.is_empty());
+
+        Self {
+            finder: Finder::new(literal).into_owned(),
+        }
+    }
+
+    /// Creates a new prefilter for the longest literal which every match of
+    /// `pattern` must start with, if any.
+    pub fn from_pattern(pattern: &str) -> Option<Self> {
+        let hir = regex_syntax::parse(pattern).ok()?;
+        let prefixes = Extractor::new().extract(&hir);
+
+        match prefixes.longest_common_prefix() {
+            Some(prefix) if !prefix.is_empty() => Some(Self::new(prefix)),
+            _ => None,
+        }
+    }
+
+    /// Returns the literal searched for.
+    pub fn literal(&self) -> &[u8] {
+        self.finder.needle()
+    }
+
+    /// Returns the span of the leftmost occurrence of the literal within
+    /// `haystack[span]`, if any.
+    pub fn find(&self, haystack: &[u8], span: Span) -> Option<Span> {
+        let start = span.start + self.finder.find(&haystack[span])?;
+        Some(Span::from(start..start + self.literal().len()))
+    }
+
+    /// Returns the span of the literal if `haystack[span]` starts with it.
+    pub fn prefix(&self, haystack: &[u8], span: Span) -> Option<Span> {
+        let end = span.start + self.literal().len();
+        if haystack[span].starts_with(self.literal()) {
+            Some(Span::from(span.start..end))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the heap memory used by the prefilter, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.finder.memory_usage()
+    }
+
+    /// Returns the length of the longest needle searched for.
+    pub fn max_needle_len(&self) -> usize {
+        self.literal().len()
+    }
+
+    /// Returns whether the prefilter is expected to be fast, which is always the
+    /// case for a single literal.
+    pub fn is_fast(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LiteralPrefilter;
+    use regex_automata::{meta::Regex, Input, Match, Span};
+
+    static I386: &[u8] = include_bytes!("../data/i386.txt");
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    #[test]
+    fn prefilter_find() {
+        let prefilter = LiteralPrefilter::new("password=");
+
+        let haystack = b"user=foo password=bar password=baz";
+        assert_eq!(
+            prefilter.find(haystack, Span::from(0..haystack.len())),
+            Some(Span::from(9..18))
+        );
+        assert_eq!(
+            prefilter.find(haystack, Span::from(10..haystack.len())),
+            Some(Span::from(22..31))
+        );
+        assert_eq!(prefilter.find(haystack, Span::from(10..30)), None);
+
+        assert_eq!(
+            prefilter.prefix(haystack, Span::from(9..haystack.len())),
+            Some(Span::from(9..18))
+        );
+        assert_eq!(prefilter.prefix(haystack, Span::from(0..18)), None);
+    }
+
+    #[test]
+    fn prefilter_from_pattern() {
+        let literal =
+            |pattern| LiteralPrefilter::from_pattern(pattern).map(|p| p.literal().to_vec());
+
+        assert_eq!(literal(r"password=\w+"), Some(b"password=".to_vec()));
+
+        assert_eq!(literal(r"foo(bar|baz)"), Some(b"fooba".to_vec()));
+
+        assert_eq!(literal(r"(foo|bar)"), None);
+
+        assert_eq!(literal(r"\w+"), None);
+
+        assert_eq!(literal(r"("), None);
+    }
+
+    /// Returns the non-overlapping matches of `regex` within `haystack`,
+    /// searching for each of them from the next occurrence of the literal of
+    /// `prefilter`.
+    fn find_iter(regex: &Regex, prefilter: &LiteralPrefilter, haystack: &[u8]) -> Vec<Match> {
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while let Some(candidate) = prefilter.find(haystack, Span::from(start..haystack.len())) {
+            // Searching within the full haystack allows look-around assertions
+            // to take the bytes before the candidate into account.
+            let input = Input::new(haystack).range(candidate.start..);
+            let Some(found) = regex.search(&input) else {
+                break;
+            };
+            // Matches start with a non-empty literal, so they are never empty.
+            start = found.end();
+            matches.push(found);
+        }
+        matches
+    }
+
+    fn search(pattern: &str, haystack: &[u8]) {
+        let prefilter = LiteralPrefilter::from_pattern(pattern).unwrap();
+        let regex = |auto_prefilter| {
+            Regex::builder()
+                .configure(Regex::config().auto_prefilter(auto_prefilter))
+                .build(pattern)
+                .unwrap()
+        };
+
+        let expected: Vec<Match> = regex(true).find_iter(haystack).collect();
+        assert_eq!(
+            find_iter(&regex(false), &prefilter, haystack),
+            expected,
+            "{:?}",
+            pattern
+        );
+    }
+
+    #[test]
+    fn prefilter_regex_search() {
+        search(
+            r"password=\w+",
+            b"user=foo password=bar password= password=baz",
+        );
+
+        search(r"password=\w+", b"no password here");
+
+        search(r"ab+", b"aababbbaab");
+
+        search(r"a\b", b"ba a aa a");
+
+        search(r"(?m)^abs\w*$", WORDS);
+
+        search(r"acc(ess|ount)\w*", WORDS);
+
+        search(r"inter[a-z]+ion", WORDS);
+
+        search(r"instruction\s+\w+", I386);
+
+        search(r"MOV[A-Z]*", I386);
+    }
+
+    #[test]
+    fn prefilter_memory_usage() {
+        let prefilter = LiteralPrefilter::new("password=");
+        assert!(prefilter.memory_usage() > prefilter.literal().len());
+    }
+}
@@ -0,0 +1,585 @@
+use crate::memmem::Finder;
+use crate::SubstringSearch;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Boolean expression over needles, which can be built directly or parsed from
+/// a string such as `("union" AND "select") AND NOT "comment"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    /// Matches haystacks containing the needle.
+    Term(Vec<u8>),
+    /// Matches haystacks matched by all the expressions, or every haystack if
+    /// there is none.
+    And(Vec<Expr>),
+    /// Matches haystacks matched by any of the expressions, or no haystack if
+    /// there is none.
+    Or(Vec<Expr>),
+    /// Matches haystacks not matched by the expression.
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    /// Parses `query`, made of needles in double quotes combined using the
+    /// `OR`, `AND` and `NOT` operators, by increasing precedence, and grouped
+    /// with parentheses. Operators are case insensitive, and double quotes and
+    /// backslashes within needles are escaped with a backslash.
+    ///
+    /// Returns an error if `query` is not a valid expression.
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            query: query.as_bytes(),
+            position: 0,
+        };
+        let expr = parser.or()?;
+        match parser.next()? {
+            None => Ok(expr),
+            Some((position, _)) => Err(QueryError::Unexpected { position }),
+        }
+    }
+}
+
+impl core::str::FromStr for Expr {
+    type Err = QueryError;
+
+    fn from_str(query: &str) -> Result<Self, QueryError> {
+        Self::parse(query)
+    }
+}
+
+/// Error returned when parsing a query fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QueryError {
+    /// The query ends where an expression or a closing parenthesis is
+    /// expected.
+    UnexpectedEnd,
+    /// The query contains an unexpected token.
+    Unexpected {
+        /// Byte offset of the token within the query.
+        position: usize,
+    },
+    /// A needle is missing its closing double quote, or contains an invalid
+    /// escape sequence.
+    InvalidNeedle {
+        /// Byte offset of the opening double quote within the query.
+        position: usize,
+    },
+}
+
+impl core::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of query"),
+            Self::Unexpected { position } => write!(f, "unexpected token at offset {position}"),
+            Self::InvalidNeedle { position } => {
+                write!(f, "invalid needle starting at offset {position}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QueryError {}
+
+/// Token of a query.
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Needle(Vec<u8>),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+/// Recursive descent parser of queries.
+struct Parser<'a> {
+    query: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Returns the next token along with its start and end, without consuming
+    /// it.
+    fn peek(&self) -> Result<Option<(usize, Token, usize)>, QueryError> {
+        let query = self.query;
+        let mut start = self.position;
+        while query.get(start).is_some_and(u8::is_ascii_whitespace) {
+            start += 1;
+        }
+
+        let Some(&b) = query.get(start) else {
+            return Ok(None);
+        };
+        let (token, end) = match b {
+            b'(' => (Token::Open, start + 1),
+            b')' => (Token::Close, start + 1),
+            b'"' => {
+                let invalid = QueryError::InvalidNeedle { position: start };
+                let mut needle = Vec::new();
+                let mut end = start + 1;
+                loop {
+                    match query.get(end) {
+                        Some(b'"') => break,
+                        Some(b'\\') => match query.get(end + 1) {
+                            Some(&escaped @ (b'"' | b'\\')) => {
+                                needle.push(escaped);
+                                end += 2;
+                            }
+                            _ => return Err(invalid),
+                        },
+                        Some(&b) => {
+                            needle.push(b);
+                            end += 1;
+                        }
+                        None => return Err(invalid),
+                    }
+                }
+                (Token::Needle(needle), end + 1)
+            }
+            _ => {
+                let end = query[start..]
+                    .iter()
+                    .position(|b| !b.is_ascii_alphabetic())
+                    .map_or(query.len(), |i| start + i);
+                let token = match &query[start..end] {
+                    word if word.eq_ignore_ascii_case(b"AND") => Token::And,
+                    word if word.eq_ignore_ascii_case(b"OR") => Token::Or,
+                    word if word.eq_ignore_ascii_case(b"NOT") => Token::Not,
+                    _ => return Err(QueryError::Unexpected { position: start }),
+                };
+                (token, end)
+            }
+        };
+        Ok(Some((start, token, end)))
+    }
+
+    /// Consumes the next token, returning it along with its start.
+    fn next(&mut self) -> Result<Option<(usize, Token)>, QueryError> {
+        Ok(self.peek()?.map(|(start, token, end)| {
+            self.position = end;
+            (start, token)
+        }))
+    }
+
+    /// Consumes the next token if it is `expected`, returning whether it was.
+    fn eat(&mut self, expected: Token) -> Result<bool, QueryError> {
+        match self.peek()? {
+            Some((_, token, end)) if token == expected => {
+                self.position = end;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, QueryError> {
+        let mut exprs = vec![self.and()?];
+        while self.eat(Token::Or)? {
+            exprs.push(self.and()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.pop().unwrap(),
+            _ => Expr::Or(exprs),
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut exprs = vec![self.not()?];
+        while self.eat(Token::And)? {
+            exprs.push(self.not()?);
+        }
+        Ok(match exprs.len() {
+            1 => exprs.pop().unwrap(),
+            _ => Expr::And(exprs),
+        })
+    }
+
+    fn not(&mut self) -> Result<Expr, QueryError> {
+        if self.eat(Token::Not)? {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+
+        match self.next()? {
+            Some((_, Token::Needle(needle))) => Ok(Expr::Term(needle)),
+            Some((_, Token::Open)) => {
+                let expr = self.or()?;
+                match self.next()? {
+                    Some((_, Token::Close)) => Ok(expr),
+                    Some((position, _)) => Err(QueryError::Unexpected { position }),
+                    None => Err(QueryError::UnexpectedEnd),
+                }
+            }
+            Some((position, _)) => Err(QueryError::Unexpected { position }),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+}
+
+/// Default relative frequencies of bytes, from 0 for the rarest to 255 for
+/// the most frequent, on a logarithmic scale. They were computed from the
+/// English text of the `data` directory.
+#[rustfmt::skip]
+static BYTE_FREQUENCIES: [u8; 256] = [
+      0,   0,   0,   0,   0,   0,   0, 106,   0,   0, 209,   0,   0,   0,   0,   0, // 0x00
+     91,  85,   0,   0,   0,   0,   0,   0,   0,   0,   0, 126,   0,   0,  63,  69, // 0x10
+    255,  14, 112, 133,  29,   0,  51,  97, 160, 160, 139, 124, 175, 164, 179, 157, // 0x20
+    171, 171, 163, 164, 141, 140, 164, 139, 165, 129, 140, 156,  80, 137,  88,  51, // 0x30
+      0, 173, 156, 169, 171, 181, 171, 150, 144, 175, 124, 113, 169, 161, 160, 167, // 0x40
+    171,  84, 168, 184, 179, 141, 142, 131, 148, 112, 118, 120,   0, 120,  78, 112, // 0x50
+      0, 213, 183, 201, 204, 228, 192, 190, 198, 216, 104, 158, 198, 193, 213, 215, // 0x60
+    195, 121, 216, 215, 222, 193, 170, 173, 162, 177, 137,  49,  78,  49,   0,   0, // 0x70
+      0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,  14,   0,   0,   0, // 0x80
+      0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0x90
+      0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0xa0
+      0, 155,   0, 165,  77,  23, 102,   0,  33, 123, 159, 111, 111,   0,  33,  87, // 0xb0
+     90, 109, 107,  76, 200, 109,  37, 108, 111, 112,  93,  92, 123, 208,  74, 120, // 0xc0
+     23, 133,   0,   0,  33,  33,   0,  68, 137,  99,  88,   0,   0,   0,   0,   0, // 0xd0
+      0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0, // 0xe0
+      0,   0,  66,  70,   0,   0,   0,   0,   0,  14,   0,   0,   0,  14, 123,   0, // 0xf0
+];
+
+/// Node of a compiled query, whose operands are ordered so as to short-circuit
+/// as early as possible.
+enum Node {
+    Term(usize),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+}
+
+/// Compiler of expressions, assigning an index to every distinct needle.
+struct Compiler<'a> {
+    needles: Vec<Vec<u8>>,
+    frequencies: &'a [u8; 256],
+}
+
+impl Compiler<'_> {
+    /// Returns the node evaluating `expr` along with its estimated probability
+    /// of matching.
+    fn compile(&mut self, expr: &Expr) -> (Node, f64) {
+        match expr {
+            Expr::Term(needle) => {
+                let index = match self.needles.iter().position(|n| n == needle) {
+                    Some(index) => index,
+                    None => {
+                        self.needles.push(needle.clone());
+                        self.needles.len() - 1
+                    }
+                };
+
+                // Bytes are assumed to be independent, so that needles made of
+                // rare bytes are the least likely to match.
+                let probability = needle
+                    .iter()
+                    .map(|&b| (self.frequencies[b as usize] as f64 + 1.0) / 256.0)
+                    .product();
+                (Node::Term(index), probability)
+            }
+            Expr::And(exprs) => {
+                // The operand the least likely to match is evaluated first.
+                let mut nodes = self.compile_all(exprs);
+                nodes.sort_by(|a, b| a.1.total_cmp(&b.1));
+                let probability = nodes.iter().map(|node| node.1).product();
+                (Node::And(unzip(nodes)), probability)
+            }
+            Expr::Or(exprs) => {
+                // The operand the most likely to match is evaluated first.
+                let mut nodes = self.compile_all(exprs);
+                nodes.sort_by(|a, b| b.1.total_cmp(&a.1));
+                let probability = 1.0 - nodes.iter().map(|node| 1.0 - node.1).product::<f64>();
+                (Node::Or(unzip(nodes)), probability)
+            }
+            Expr::Not(expr) => {
+                let (node, probability) = self.compile(expr);
+                (Node::Not(Box::new(node)), 1.0 - probability)
+            }
+        }
+    }
+
+    fn compile_all(&mut self, exprs: &[Expr]) -> Vec<(Node, f64)> {
+        exprs.iter().map(|expr| self.compile(expr)).collect()
+    }
+}
+
+fn unzip(nodes: Vec<(Node, f64)>) -> Vec<Node> {
+    nodes.into_iter().map(|(node, _)| node).collect()
+}
+
+/// Boolean query compiled into a searcher for each distinct needle.
+///
+/// Queries are evaluated with short-circuiting, searching the haystack for as
+/// few needles as possible. Operands are ordered by their estimated probability
+/// of matching, derived from the frequency of the bytes of the needles, so that
+/// `AND` evaluates its least likely operand first and `OR` its most likely.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::query::Query;
+///
+/// let query = Query::parse(r#"("union" AND "select") AND NOT "comment""#).unwrap();
+///
+/// assert!(query.is_match(b"1 union select password"));
+/// assert!(!query.is_match(b"select a comment"));
+///
+/// let evaluation = query.evaluate(b"union select /* comment */");
+/// assert!(!evaluation.is_match());
+/// assert_eq!(evaluation.term(2), Some(true));
+/// ```
+pub struct Query {
+    finders: Vec<Finder<'static>>,
+    root: Node,
+}
+
+impl Query {
+    /// Compiles `expr` using the default byte frequencies, which suit text.
+    pub fn new(expr: &Expr) -> Self {
+        Self::with_byte_frequencies(expr, &BYTE_FREQUENCIES)
+    }
+
+    /// Same as `new` but estimates how likely needles are to match using
+    /// `frequencies`, the relative frequency of each byte in the haystacks
+    /// from 0 for the rarest to 255 for the most frequent.
+    pub fn with_byte_frequencies(expr: &Expr, frequencies: &[u8; 256]) -> Self {
+        let mut compiler = Compiler {
+            needles: Vec::new(),
+            frequencies,
+        };
+        let (root, _) = compiler.compile(expr);
+
+        let finders = compiler
+            .needles
+            .iter()
+            .map(|needle| Finder::new(needle).into_owned())
+            .collect();
+        Self { finders, root }
+    }
+
+    /// Parses `query` as done by `Expr::parse` and compiles it using the
+    /// default byte frequencies.
+    pub fn parse(query: &str) -> Result<Self, QueryError> {
+        Ok(Self::new(&Expr::parse(query)?))
+    }
+
+    /// Returns the number of distinct needles of the query, which are indexed
+    /// in order of first appearance.
+    pub fn term_count(&self) -> usize {
+        self.finders.len()
+    }
+
+    /// Returns the needle of term `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn term(&self, index: usize) -> &[u8] {
+        self.finders[index].needle()
+    }
+
+    /// Returns whether `haystack` matches the query.
+    pub fn is_match(&self, haystack: &[u8]) -> bool {
+        self.evaluate(haystack).is_match()
+    }
+
+    /// Evaluates the query against `haystack`, reporting which terms were
+    /// found among those searched before the result was known.
+    pub fn evaluate(&self, haystack: &[u8]) -> Evaluation {
+        let mut terms = vec![None; self.finders.len()];
+        let matched = self.eval(&self.root, haystack, &mut terms);
+        Evaluation { matched, terms }
+    }
+
+    /// Same as `evaluate` but searches `haystack` for every term, so that
+    /// all the terms found are reported.
+    pub fn evaluate_all(&self, haystack: &[u8]) -> Evaluation {
+        let mut terms: Vec<Option<bool>> = self
+            .finders
+            .iter()
+            .map(|finder| Some(finder.search_in(haystack)))
+            .collect();
+        let matched = self.eval(&self.root, haystack, &mut terms);
+        Evaluation { matched, terms }
+    }
+
+    fn eval(&self, node: &Node, haystack: &[u8], terms: &mut [Option<bool>]) -> bool {
+        match node {
+            Node::Term(index) => {
+                *terms[*index].get_or_insert_with(|| self.finders[*index].search_in(haystack))
+            }
+            Node::And(nodes) => nodes.iter().all(|node| self.eval(node, haystack, terms)),
+            Node::Or(nodes) => nodes.iter().any(|node| self.eval(node, haystack, terms)),
+            Node::Not(node) => !self.eval(node, haystack, terms),
+        }
+    }
+}
+
+/// Result of evaluating a `Query` against a haystack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    matched: bool,
+    terms: Vec<Option<bool>>,
+}
+
+impl Evaluation {
+    /// Returns whether the haystack matches the query.
+    pub fn is_match(&self) -> bool {
+        self.matched
+    }
+
+    /// Returns whether the needle of term `index` was found, or `None` if the
+    /// haystack was not searched for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn term(&self, index: usize) -> Option<bool> {
+        self.terms[index]
+    }
+
+    /// Returns an iterator over the indices of the terms found, in increasing
+    /// order.
+    pub fn matched_terms(&self) -> impl Iterator<Item = usize> + '_ {
+        self.terms
+            .iter()
+            .enumerate()
+            .filter(|(_, found)| **found == Some(true))
+            .map(|(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expr, Query, QueryError};
+
+    static WORDS: &[u8] = include_bytes!("../data/words.txt");
+
+    fn term(needle: &str) -> Expr {
+        Expr::Term(needle.as_bytes().to_vec())
+    }
+
+    /// Naive evaluation of `expr`.
+    fn naive(expr: &Expr, haystack: &[u8]) -> bool {
+        match expr {
+            Expr::Term(needle) => memchr::memmem::find(haystack, needle).is_some(),
+            Expr::And(exprs) => exprs.iter().all(|expr| naive(expr, haystack)),
+            Expr::Or(exprs) => exprs.iter().any(|expr| naive(expr, haystack)),
+            Expr::Not(expr) => !naive(expr, haystack),
+        }
+    }
+
+    #[test]
+    fn query_parse() {
+        assert_eq!(
+            Expr::parse(r#"("union" AND "select") and not "comment""#),
+            Ok(Expr::And(vec![
+                Expr::And(vec![term("union"), term("select")]),
+                Expr::Not(Box::new(term("comment"))),
+            ]))
+        );
+        assert_eq!(
+            r#""a" OR "b" AND NOT NOT "c" OR "" "#.parse(),
+            Ok(Expr::Or(vec![
+                term("a"),
+                Expr::And(vec![
+                    term("b"),
+                    Expr::Not(Box::new(Expr::Not(Box::new(term("c"))))),
+                ]),
+                term(""),
+            ]))
+        );
+        assert_eq!(Expr::parse(r#""a\"b\\""#), Ok(term("a\"b\\")));
+        assert_eq!(Expr::parse(r#"(("x"))"#), Ok(term("x")));
+
+        assert_eq!(Expr::parse(""), Err(QueryError::UnexpectedEnd));
+        assert_eq!(Expr::parse(r#""a" AND"#), Err(QueryError::UnexpectedEnd));
+        assert_eq!(Expr::parse(r#"("a""#), Err(QueryError::UnexpectedEnd));
+        assert_eq!(
+            Expr::parse(r#""a" "b""#),
+            Err(QueryError::Unexpected { position: 4 })
+        );
+        assert_eq!(
+            Expr::parse(r#""a" XOR "b""#),
+            Err(QueryError::Unexpected { position: 4 })
+        );
+        assert_eq!(
+            Expr::parse(r#"("a" AND )"#),
+            Err(QueryError::Unexpected { position: 9 })
+        );
+        assert_eq!(
+            Expr::parse(r#""a" OR "b"#),
+            Err(QueryError::InvalidNeedle { position: 7 })
+        );
+        assert_eq!(
+            Expr::parse(r#""a\b""#),
+            Err(QueryError::InvalidNeedle { position: 0 })
+        );
+    }
+
+    #[test]
+    fn query_match_words() {
+        let queries = [
+            r#""a""#,
+            r#""ation" AND NOT "s""#,
+            r#""qu" OR "zz" OR "xy""#,
+            r#"("in" OR "un") AND ("able" OR "ing") AND NOT ("q" OR "z")"#,
+            r#"NOT ("e" AND "a") OR "ee""#,
+            r#""" AND NOT """#,
+        ];
+
+        for query in queries {
+            let expr = Expr::parse(query).unwrap();
+            let compiled = Query::new(&expr);
+            for haystack in WORDS.split(|&b| b == b'\n') {
+                let expected = naive(&expr, haystack);
+                let evaluation = compiled.evaluate(haystack);
+                assert_eq!(evaluation.is_match(), expected, "{query} {haystack:?}");
+
+                // Terms searched are reported correctly.
+                let all = compiled.evaluate_all(haystack);
+                assert_eq!(all.is_match(), expected);
+                for index in 0..compiled.term_count() {
+                    let found = naive(&Expr::Term(compiled.term(index).to_vec()), haystack);
+                    assert_eq!(all.term(index), Some(found));
+                    assert!(evaluation.term(index).is_none_or(|term| term == found));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn query_short_circuit() {
+        let query = Query::parse(r#""e" AND "xz" AND ("e" OR "q")"#).unwrap();
+        assert_eq!(query.term_count(), 3);
+        assert_eq!(query.term(1), b"xz");
+
+        // The rarest needle is searched first, which is enough to reject the
+        // haystack.
+        let evaluation = query.evaluate(b"eeeee");
+        assert!(!evaluation.is_match());
+        assert_eq!(evaluation.term(0), None);
+        assert_eq!(evaluation.term(1), Some(false));
+        assert!(evaluation.matched_terms().next().is_none());
+
+        let evaluation = query.evaluate_all(b"eeeee");
+        assert!(evaluation.matched_terms().eq([0]));
+
+        // The most frequent operand of `OR` is searched first.
+        let evaluation = query.evaluate(b"xze");
+        assert!(evaluation.is_match());
+        assert_eq!(evaluation.term(2), None);
+        assert!(evaluation.matched_terms().eq([0, 1]));
+
+        // Frequencies can be customized.
+        let mut frequencies = [0; 256];
+        frequencies[b'x' as usize] = 255;
+        frequencies[b'z' as usize] = 255;
+        let query =
+            Query::with_byte_frequencies(&Expr::parse(r#""e" AND "xz""#).unwrap(), &frequencies);
+        let evaluation = query.evaluate(b"xz");
+        assert_eq!(evaluation.term(0), Some(false));
+        assert_eq!(evaluation.term(1), None);
+    }
+}
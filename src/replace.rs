@@ -0,0 +1,163 @@
+use crate::memmem::Matches;
+use crate::SubstringSearch;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+/// Calls `write` with the parts of `haystack` between the matches of
+/// `searcher`, interleaved with `replacement`.
+fn replace_with<S: SubstringSearch, E, F: FnMut(&[u8]) -> Result<(), E>>(
+    searcher: S,
+    haystack: &[u8],
+    replacement: &[u8],
+    mut write: F,
+) -> Result<(), E> {
+    let size = searcher.needle().len();
+
+    let mut start = 0;
+    for position in Matches::new(searcher, haystack) {
+        write(&haystack[start..position])?;
+        write(replacement)?;
+        start = position + size;
+    }
+    write(&haystack[start..])
+}
+
+/// Returns a copy of `haystack` where all the non-overlapping matches of
+/// `searcher` are replaced with `replacement`, with the same semantics as
+/// `str::replace`.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::{memmem::Finder, replace::replace_all};
+///
+/// let finder = Finder::new("secret");
+///
+/// assert_eq!(replace_all(&finder, b"a secret b secret", b"-"), b"a - b -");
+/// ```
+pub fn replace_all<S: SubstringSearch>(
+    searcher: S,
+    haystack: &[u8],
+    replacement: &[u8],
+) -> Vec<u8> {
+    let mut replaced = Vec::with_capacity(haystack.len());
+    // Extending a vector never fails.
+    let _ = replace_with(searcher, haystack, replacement, |part| {
+        replaced.extend_from_slice(part);
+        Ok::<_, Infallible>(())
+    });
+    replaced
+}
+
+/// Same as `replace_all` but writes the result into `writer` instead of
+/// allocating a new buffer.
+#[cfg(feature = "std")]
+pub fn replace_all_into<S: SubstringSearch, W: Write>(
+    searcher: S,
+    haystack: &[u8],
+    replacement: &[u8],
+    writer: &mut W,
+) -> io::Result<()> {
+    replace_with(searcher, haystack, replacement, |part| {
+        writer.write_all(part)
+    })
+}
+
+/// Overwrites all the non-overlapping matches of `searcher` within `haystack`
+/// with `replacement` in place, for example to mask secrets. Returns the number
+/// of matches overwritten.
+///
+/// # Panics
+///
+/// Panics if `replacement` does not have the same length as the needle.
+pub fn overwrite_all<S: SubstringSearch>(
+    searcher: S,
+    haystack: &mut [u8],
+    replacement: &[u8],
+) -> usize {
+    let size = searcher.needle().len();
+    assert_eq!(size, replacement.len());
+
+    // Overwriting empty matches would not change anything.
+    if size == 0 {
+        return 0;
+    }
+
+    let mut count = 0;
+    let mut start = 0;
+    while let Some(i) = searcher.find(&haystack[start..]) {
+        let position = start + i;
+        haystack[position..position + size].copy_from_slice(replacement);
+        start = position + size;
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{overwrite_all, replace_all, replace_all_into};
+    use crate::memmem::Finder;
+
+    static WORDS: &str = include_str!("../data/words.txt");
+
+    fn search(haystack: &str, needle: &str) {
+        let finder = Finder::new(needle);
+        let bytes = haystack.as_bytes();
+
+        for replacement in ["", "-", "+++"] {
+            let expected = haystack.replace(needle, replacement).into_bytes();
+
+            assert_eq!(
+                replace_all(&finder, bytes, replacement.as_bytes()),
+                expected
+            );
+
+            let mut replaced = Vec::new();
+            replace_all_into(&finder, bytes, replacement.as_bytes(), &mut replaced).unwrap();
+            assert_eq!(replaced, expected);
+        }
+
+        let mask = "*".repeat(needle.len());
+        let mut overwritten = bytes.to_vec();
+        let count = overwrite_all(&finder, &mut overwritten, mask.as_bytes());
+        assert_eq!(overwritten, haystack.replace(needle, &mask).into_bytes());
+        if !needle.is_empty() {
+            assert_eq!(count, haystack.matches(needle).count());
+        }
+    }
+
+    #[test]
+    fn replace_search() {
+        let haystacks = [
+            "",
+            "a",
+            "aaa",
+            "aaaaa",
+            "abab",
+            "password=hunter2 password=hunter2",
+        ];
+        let needles = ["", "a", "aa", "ab", "hunter2", "zzz"];
+
+        for haystack in haystacks {
+            for needle in needles {
+                search(haystack, needle);
+            }
+        }
+    }
+
+    #[test]
+    fn replace_search_words() {
+        for needle in ["\n", "e", "ing", "ation\n", "zzz"] {
+            search(WORDS, needle);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn overwrite_different_length() {
+        overwrite_all(Finder::new("abc"), &mut b"abc".to_vec(), b"**");
+    }
+}
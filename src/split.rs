@@ -0,0 +1,243 @@
+use crate::memmem::{FindRevIter, FinderRev, Matches};
+use crate::SubstringSearch;
+
+/// Returns an iterator over the parts of `haystack` separated by the
+/// non-overlapping matches of `searcher`, with the same semantics as
+/// `str::split`.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::{memmem::Finder, split::split};
+///
+/// let finder = Finder::new("||");
+/// let fields: Vec<&[u8]> = split(&finder, b"a||b||||c||").collect();
+///
+/// assert_eq!(fields, [&b"a"[..], b"b", b"", b"c", b""]);
+/// ```
+pub fn split<S: SubstringSearch>(searcher: S, haystack: &[u8]) -> Split<'_, S> {
+    Split {
+        matches: Matches::new(searcher, haystack),
+        haystack,
+        start: 0,
+        finished: false,
+    }
+}
+
+/// Same as `split` but returns at most `n` parts, the last of which contains
+/// the rest of the haystack, with the same semantics as `str::splitn`.
+pub fn splitn<S: SubstringSearch>(searcher: S, haystack: &[u8], n: usize) -> SplitN<'_, S> {
+    SplitN {
+        split: split(searcher, haystack),
+        remaining: n,
+    }
+}
+
+/// Same as `split` but returns the parts from right to left, with the same
+/// semantics as `str::rsplit`. Searchers only search forwards, so the parts are
+/// found lazily using the reverse `finder` instead.
+///
+/// # Example
+///
+/// ```
+/// use sliceslice::{memmem::FinderRev, split::rsplit};
+///
+/// let finder = FinderRev::new("||");
+/// let fields: Vec<&[u8]> = rsplit(&finder, b"a||b||||c").collect();
+///
+/// assert_eq!(fields, [&b"c"[..], b"", b"b", b"a"]);
+/// ```
+pub fn rsplit<'h, 'n>(finder: &'n FinderRev<'_>, haystack: &'h [u8]) -> RSplit<'h, 'n> {
+    RSplit {
+        matches: finder.rfind_iter(haystack),
+        size: finder.needle().len(),
+        haystack,
+        end: haystack.len(),
+        finished: false,
+    }
+}
+
+/// Same as `split` but includes the separator at the end of each part, with the
+/// same semantics as `str::split_inclusive`. In particular, a trailing
+/// separator does not produce an empty last part.
+pub fn split_inclusive<S: SubstringSearch>(searcher: S, haystack: &[u8]) -> SplitInclusive<'_, S> {
+    SplitInclusive {
+        split: split(searcher, haystack),
+    }
+}
+
+/// Iterator over the parts of a haystack separated by the matches of a
+/// searcher, returned by `split`.
+pub struct Split<'h, S> {
+    matches: Matches<'h, S>,
+    haystack: &'h [u8],
+    start: usize,
+    finished: bool,
+}
+
+impl<'h, S: SubstringSearch> Split<'h, S> {
+    fn rest(&mut self) -> Option<&'h [u8]> {
+        if self.finished {
+            return None;
+        }
+
+        self.finished = true;
+        Some(&self.haystack[self.start..])
+    }
+}
+
+impl<'h, S: SubstringSearch> Iterator for Split<'h, S> {
+    type Item = &'h [u8];
+
+    fn next(&mut self) -> Option<&'h [u8]> {
+        if self.finished {
+            return None;
+        }
+
+        match self.matches.next() {
+            Some(position) => {
+                let start = self.start;
+                self.start = position + self.matches.needle().len();
+                Some(&self.haystack[start..position])
+            }
+            None => self.rest(),
+        }
+    }
+}
+
+/// Iterator over at most `n` parts of a haystack separated by the matches of a
+/// searcher, returned by `splitn`.
+pub struct SplitN<'h, S> {
+    split: Split<'h, S>,
+    remaining: usize,
+}
+
+impl<'h, S: SubstringSearch> Iterator for SplitN<'h, S> {
+    type Item = &'h [u8];
+
+    fn next(&mut self) -> Option<&'h [u8]> {
+        match self.remaining {
+            0 => None,
+            1 => {
+                self.remaining = 0;
+                self.split.rest()
+            }
+            _ => {
+                self.remaining -= 1;
+                self.split.next()
+            }
+        }
+    }
+}
+
+/// Iterator over the parts of a haystack separated by the matches of a
+/// reverse finder from right to left, returned by `rsplit`.
+pub struct RSplit<'h, 'n> {
+    matches: FindRevIter<'h, 'n>,
+    size: usize,
+    haystack: &'h [u8],
+    end: usize,
+    finished: bool,
+}
+
+impl<'h> Iterator for RSplit<'h, '_> {
+    type Item = &'h [u8];
+
+    fn next(&mut self) -> Option<&'h [u8]> {
+        if self.finished {
+            return None;
+        }
+
+        let end = self.end;
+        match self.matches.next() {
+            Some(position) => {
+                self.end = position;
+                Some(&self.haystack[position + self.size..end])
+            }
+            None => {
+                self.finished = true;
+                Some(&self.haystack[..end])
+            }
+        }
+    }
+}
+
+/// Iterator over the parts of a haystack terminated by the matches of a
+/// searcher, returned by `split_inclusive`.
+pub struct SplitInclusive<'h, S> {
+    split: Split<'h, S>,
+}
+
+impl<'h, S: SubstringSearch> Iterator for SplitInclusive<'h, S> {
+    type Item = &'h [u8];
+
+    fn next(&mut self) -> Option<&'h [u8]> {
+        let split = &mut self.split;
+        if split.finished {
+            return None;
+        }
+
+        match split.matches.next() {
+            Some(position) => {
+                let start = split.start;
+                split.start = position + split.matches.needle().len();
+                Some(&split.haystack[start..split.start])
+            }
+            // Unlike `split`, the last part is omitted when empty.
+            None => split.rest().filter(|rest| !rest.is_empty()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rsplit, split, split_inclusive, splitn};
+    use crate::memmem::{Finder, FinderRev};
+
+    static WORDS: &str = include_str!("../data/words.txt");
+
+    fn search(haystack: &str, needle: &str) {
+        let finder = Finder::new(needle);
+        let bytes = haystack.as_bytes();
+
+        assert!(split(&finder, bytes).eq(haystack.split(needle).map(str::as_bytes)));
+        for n in 0..4 {
+            assert!(splitn(&finder, bytes, n).eq(haystack.splitn(n, needle).map(str::as_bytes)));
+        }
+        let finder_rev = FinderRev::new(needle);
+        assert!(rsplit(&finder_rev, bytes).eq(haystack.rsplit(needle).map(str::as_bytes)));
+        assert!(
+            split_inclusive(&finder, bytes).eq(haystack.split_inclusive(needle).map(str::as_bytes))
+        );
+    }
+
+    #[test]
+    fn split_search() {
+        let haystacks = [
+            "",
+            "a",
+            "aaa",
+            "abab",
+            "\r\n",
+            "a\r\nb",
+            "a\r\nb\r\n",
+            "||",
+            "a||b||||c||",
+            "--boundary\r\nfoo\r\n--boundary\r\nbar\r\n--boundary--",
+        ];
+        let needles = ["", "a", "aa", "ab", "\r\n", "||", "--boundary", "zzz"];
+
+        for haystack in haystacks {
+            for needle in needles {
+                search(haystack, needle);
+            }
+        }
+    }
+
+    #[test]
+    fn split_search_words() {
+        for needle in ["\n", "e", "ing", "ation\n", "zzz"] {
+            search(WORDS, needle);
+        }
+    }
+}
@@ -1,8 +1,11 @@
 #![allow(clippy::missing_safety_doc)]
 
-use crate::{Needle, NeedleWithSize, Searcher, Vector, VectorHash};
+use crate::{
+    Needle, NeedleSnapshot, NeedleWithSize, Searcher, SearcherError, Vector, VectorHash,
+    WordBoundary,
+};
 #[cfg(feature = "stdsimd")]
-use std::simd::{cmp::SimdPartialEq, *};
+use core::simd::{cmp::SimdPartialEq, *};
 
 trait SupportedMaskLateCount {}
 
@@ -27,7 +30,7 @@ where
 
     #[inline]
     unsafe fn load(a: *const u8) -> Self {
-        std::ptr::read_unaligned(a as *const Self)
+        core::ptr::read_unaligned(a as *const Self)
     }
 
     #[inline]
@@ -71,6 +74,7 @@ where
 pub struct StdSimdSearcher<N: Needle> {
     needle: N,
     position: usize,
+    size: usize,
     simd32_hash: VectorHash<Simd32>,
 }
 
@@ -84,6 +88,29 @@ impl<N: Needle> Searcher<N> for StdSimdSearcher<N> {
     }
 }
 
+impl<N: Needle> crate::SubstringSearch for StdSimdSearcher<N> {
+    fn needle(&self) -> &[u8] {
+        self.needle.as_bytes()
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        self.inlined_search_in(haystack)
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let mut found = None;
+        self.search_matches_in(haystack, &mut |position| {
+            found = Some(position);
+            true
+        });
+        found
+    }
+}
+
 impl<N: Needle> StdSimdSearcher<N> {
     /// Creates a new searcher for `needle`. By default, `position` is set to
     /// the last character in the needle.
@@ -91,7 +118,8 @@ impl<N: Needle> StdSimdSearcher<N> {
     /// # Panics
     ///
     /// Panics if `needle` is empty or if the associated `SIZE` constant does
-    /// not correspond to the actual size of `needle`.
+    /// not correspond to the actual size of `needle`. Searching also panics if
+    /// the size of `needle` has changed since then.
     pub fn new(needle: N) -> Self {
         // Wrapping prevents panicking on unsigned integer underflow when
         // `needle` is empty.
@@ -108,31 +136,52 @@ impl<N: Needle> StdSimdSearcher<N> {
     /// actual size of `needle`.
     #[inline]
     pub fn with_position(needle: N, position: usize) -> Self {
-        // Implicitly checks that the needle is not empty because position is an
-        // unsized integer.
-        assert!(position < needle.size());
+        match Self::try_with_position(needle, position) {
+            Ok(searcher) => searcher,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Same as `new` but returns an error instead of panicking if `needle` is
+    /// invalid.
+    pub fn try_new(needle: N) -> Result<Self, SearcherError> {
+        // Wrapping prevents panicking on unsigned integer underflow when
+        // `needle` is empty.
+        let position = needle.size().wrapping_sub(1);
+        Self::try_with_position(needle, position)
+    }
+
+    /// Same as `with_position` but returns an error instead of panicking if
+    /// `needle` or `position` is invalid.
+    #[inline]
+    pub fn try_with_position(needle: N, position: usize) -> Result<Self, SearcherError> {
+        SearcherError::check(&needle, position)?;
 
         let bytes = needle.as_bytes();
-        if let Some(size) = N::SIZE {
-            assert_eq!(size, bytes.len());
-        }
 
         let simd32_hash = unsafe { VectorHash::new(bytes[0], bytes[position]) };
 
-        Self {
+        Ok(Self {
             position,
+            size: bytes.len(),
             simd32_hash,
             needle,
-        }
+        })
     }
 
-    /// Inlined version of `search_in` for hot call sites.
     #[inline]
-    pub fn inlined_search_in(&self, haystack: &[u8]) -> bool {
-        let needle = self.needle();
+    fn search_filtered_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
+        // The needle is only borrowed once, so that its size cannot change
+        // during the search.
+        let needle = &NeedleSnapshot::new(&self.needle, self.size);
 
         if haystack.len() <= needle.size() {
-            return haystack == needle.as_bytes();
+            return crate::short_search_in(needle, self.position, haystack, verify, f);
         }
 
         let position = self.position();
@@ -143,22 +192,30 @@ impl<N: Needle> StdSimdSearcher<N> {
         } else if end < Simd4::LANES {
             let hash = from_hash::<32, 2>(&self.simd32_hash);
             unsafe {
-                crate::vector_search_in_default_version(needle, position, haystack, end, &hash)
+                crate::vector_search_in_default_version(
+                    needle, position, haystack, end, &hash, verify, f,
+                )
             }
         } else if end < Simd8::LANES {
             let hash = from_hash::<32, 4>(&self.simd32_hash);
             unsafe {
-                crate::vector_search_in_default_version(needle, position, haystack, end, &hash)
+                crate::vector_search_in_default_version(
+                    needle, position, haystack, end, &hash, verify, f,
+                )
             }
         } else if end < Simd16::LANES {
             let hash = from_hash::<32, 8>(&self.simd32_hash);
             unsafe {
-                crate::vector_search_in_default_version(needle, position, haystack, end, &hash)
+                crate::vector_search_in_default_version(
+                    needle, position, haystack, end, &hash, verify, f,
+                )
             }
         } else if end < Simd32::LANES {
             let hash = from_hash::<32, 16>(&self.simd32_hash);
             unsafe {
-                crate::vector_search_in_default_version(needle, position, haystack, end, &hash)
+                crate::vector_search_in_default_version(
+                    needle, position, haystack, end, &hash, verify, f,
+                )
             }
         } else {
             unsafe {
@@ -168,29 +225,80 @@ impl<N: Needle> StdSimdSearcher<N> {
                     haystack,
                     end,
                     &self.simd32_hash,
+                    verify,
+                    f,
                 )
             }
         }
     }
 
+    #[inline]
+    fn search_matches_in<F: FnMut(usize) -> bool>(&self, haystack: &[u8], f: &mut F) -> bool {
+        self.search_filtered_in(haystack, true, f)
+    }
+
+    /// Inlined version of `search_in` for hot call sites.
+    #[inline]
+    pub fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+
     /// Performs a substring search for the `needle` within `haystack`.
     pub fn search_in(&self, haystack: &[u8]) -> bool {
         self.inlined_search_in(haystack)
     }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    pub fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        let size = self.size;
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, size)
+        })
+    }
+
+    /// Calls `verify` with every candidate position of `haystack` at which
+    /// both the first byte of the needle and the byte at `position` match, in
+    /// increasing order, stopping as soon as `verify` returns `true`. Returns
+    /// whether any candidate was accepted.
+    ///
+    /// This exposes the SIMD filter used by `search_in` without the final
+    /// comparison with the needle, so that custom matchers can decide what
+    /// counts as a match. Every candidate leaves room for a full needle, i.e.
+    /// `candidate + needle.len() <= haystack.len()`.
+    pub fn search_candidates_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut verify: F,
+    ) -> bool {
+        self.search_filtered_in(haystack, false, &mut verify)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::StdSimdSearcher;
+    use crate::WordBoundary;
 
     impl crate::tests::TestSearcher for StdSimdSearcher<&[u8]> {
-        fn with_position(needle: &'static [u8], position: usize) -> StdSimdSearcher<&[u8]> {
+        fn with_position(needle: &'static [u8], position: usize) -> Self {
             StdSimdSearcher::with_position(needle, position)
         }
 
         fn search_in(&self, haystack: &[u8]) -> bool {
             StdSimdSearcher::search_in(self, haystack)
         }
+
+        fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+            StdSimdSearcher::search_word_in(self, haystack, boundary)
+        }
+
+        fn search_candidates_in(
+            &self,
+            haystack: &[u8],
+            verify: &mut dyn FnMut(usize) -> bool,
+        ) -> bool {
+            StdSimdSearcher::search_candidates_in(self, haystack, verify)
+        }
     }
 
     crate::generate_tests!(std_simd_searcher, StdSimdSearcher);
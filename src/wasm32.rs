@@ -1,8 +1,8 @@
 #![allow(clippy::missing_safety_doc)]
 
-use crate::{Needle, NeedleWithSize, Searcher, Vector, VectorHash};
+use crate::{Needle, NeedleWithSize, Searcher, SearcherError, Vector, VectorHash, WordBoundary};
 #[cfg(target_arch = "wasm32")]
-use std::arch::wasm32::*;
+use core::arch::wasm32::*;
 
 impl Vector for v128 {
     const LANES: usize = 16;
@@ -17,7 +17,7 @@ impl Vector for v128 {
     #[inline]
     #[target_feature(enable = "simd128")]
     unsafe fn load(a: *const u8) -> Self {
-        std::ptr::read_unaligned(a as *const v128)
+        core::ptr::read_unaligned(a as *const v128)
     }
 
     #[inline]
@@ -57,7 +57,7 @@ impl Vector for v64 {
     #[inline]
     #[target_feature(enable = "simd128")]
     unsafe fn load(a: *const u8) -> Self {
-        Self(u64x2_splat(std::ptr::read_unaligned(a as *const u64)))
+        Self(u64x2_splat(core::ptr::read_unaligned(a as *const u64)))
     }
 
     #[inline]
@@ -103,7 +103,7 @@ impl Vector for v32 {
     #[inline]
     #[target_feature(enable = "simd128")]
     unsafe fn load(a: *const u8) -> Self {
-        Self(u32x4_splat(std::ptr::read_unaligned(a as *const u32)))
+        Self(u32x4_splat(core::ptr::read_unaligned(a as *const u32)))
     }
 
     #[inline]
@@ -149,7 +149,7 @@ impl Vector for v16 {
     #[inline]
     #[target_feature(enable = "simd128")]
     unsafe fn load(a: *const u8) -> Self {
-        Self(u16x8_splat(std::ptr::read_unaligned(a as *const u16)))
+        Self(u16x8_splat(core::ptr::read_unaligned(a as *const u16)))
     }
 
     #[inline]
@@ -194,6 +194,41 @@ impl<N: Needle> Searcher<N> for Wasm32Searcher<N> {
     }
 }
 
+// Constructing a `Wasm32Searcher` requires SIMD128 support, which guarantees
+// that its methods can be safely called from the trait.
+impl<N: Needle> crate::SubstringSearch for Wasm32Searcher<N> {
+    fn needle(&self) -> &[u8] {
+        self.needle.as_bytes()
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        unsafe { self.inlined_search_in(haystack) }
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let mut found = None;
+        unsafe {
+            self.search_matches_in(haystack, &mut |position| {
+                found = Some(position);
+                true
+            })
+        };
+        found
+    }
+
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        unsafe { self.batch_search_in(haystacks.iter().copied(), matches) }
+    }
+
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        unsafe { self.batch_search_in(crate::offsets_haystacks(offsets, data), matches) }
+    }
+}
+
 impl<N: Needle> Wasm32Searcher<N> {
     /// Creates a new searcher for `needle`. By default, `position` is set to
     /// the last character in the needle.
@@ -220,32 +255,52 @@ impl<N: Needle> Wasm32Searcher<N> {
     #[inline]
     #[target_feature(enable = "simd128")]
     pub unsafe fn with_position(needle: N, position: usize) -> Self {
-        // Implicitly checks that the needle is not empty because position is an
-        // unsized integer.
-        assert!(position < needle.size());
+        match Self::try_with_position(needle, position) {
+            Ok(searcher) => searcher,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Same as `new` but returns an error instead of panicking if `needle` is
+    /// invalid.
+    #[target_feature(enable = "simd128")]
+    pub unsafe fn try_new(needle: N) -> Result<Self, SearcherError> {
+        // Wrapping prevents panicking on unsigned integer underflow when
+        // `needle` is empty.
+        let position = needle.size().wrapping_sub(1);
+        Self::try_with_position(needle, position)
+    }
+
+    /// Same as `with_position` but returns an error instead of panicking if
+    /// `needle` or `position` is invalid.
+    #[inline]
+    #[target_feature(enable = "simd128")]
+    pub unsafe fn try_with_position(needle: N, position: usize) -> Result<Self, SearcherError> {
+        SearcherError::check(&needle, position)?;
 
         let bytes = needle.as_bytes();
-        if let Some(size) = N::SIZE {
-            assert_eq!(size, bytes.len());
-        }
 
         let v128_hash = VectorHash::new(bytes[0], bytes[position]);
 
-        Self {
+        Ok(Self {
             position,
             v128_hash,
             needle,
-        }
+        })
     }
 
-    /// Inlined version of `search_in` for hot call sites.
     #[inline]
     #[target_feature(enable = "simd128")]
-    pub unsafe fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+    unsafe fn search_filtered_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let needle = self.needle();
 
         if haystack.len() <= needle.size() {
-            return haystack == needle.as_bytes();
+            return crate::short_search_in(needle, self.position, haystack, verify, f);
         }
 
         let position = self.position();
@@ -255,38 +310,116 @@ impl<N: Needle> Wasm32Searcher<N> {
             unreachable!();
         } else if end < v32::LANES {
             let hash = VectorHash::<v16>::from(&self.v128_hash);
-            crate::vector_search_in_simd128_version(needle, position, haystack, end, &hash)
+            crate::vector_search_in_simd128_version(
+                needle, position, haystack, end, &hash, verify, f,
+            )
         } else if end < v64::LANES {
             let hash = VectorHash::<v32>::from(&self.v128_hash);
-            crate::vector_search_in_simd128_version(needle, position, haystack, end, &hash)
+            crate::vector_search_in_simd128_version(
+                needle, position, haystack, end, &hash, verify, f,
+            )
         } else if end < v128::LANES {
             let hash = VectorHash::<v64>::from(&self.v128_hash);
-            crate::vector_search_in_simd128_version(needle, position, haystack, end, &hash)
+            crate::vector_search_in_simd128_version(
+                needle, position, haystack, end, &hash, verify, f,
+            )
         } else {
             let hash = &self.v128_hash;
-            crate::vector_search_in_simd128_version(needle, position, haystack, end, hash)
+            crate::vector_search_in_simd128_version(
+                needle, position, haystack, end, hash, verify, f,
+            )
         }
     }
 
+    #[inline]
+    #[target_feature(enable = "simd128")]
+    unsafe fn search_matches_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        self.search_filtered_in(haystack, true, f)
+    }
+
+    /// Inlined version of `search_in` for hot call sites.
+    #[inline]
+    #[target_feature(enable = "simd128")]
+    pub unsafe fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+
     /// Performs a substring search for the `needle` within `haystack`.
     #[target_feature(enable = "simd128")]
     pub unsafe fn search_in(&self, haystack: &[u8]) -> bool {
         self.inlined_search_in(haystack)
     }
+
+    /// Searches each of `haystacks` in turn with SIMD128 enabled for the whole
+    /// batch, so that `inlined_search_in` is inlined into the loop.
+    #[target_feature(enable = "simd128")]
+    unsafe fn batch_search_in<'h, I: ExactSizeIterator<Item = &'h [u8]>>(
+        &self,
+        haystacks: I,
+        matches: &mut [u64],
+    ) {
+        crate::batch_search_in(haystacks, matches, |haystack| {
+            self.inlined_search_in(haystack)
+        })
+    }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    #[target_feature(enable = "simd128")]
+    pub unsafe fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        let size = self.needle.size();
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, size)
+        })
+    }
+
+    /// Calls `verify` with every candidate position of `haystack` at which
+    /// both the first byte of the needle and the byte at `position` match, in
+    /// increasing order, stopping as soon as `verify` returns `true`. Returns
+    /// whether any candidate was accepted.
+    ///
+    /// This exposes the SIMD filter used by `search_in` without the final
+    /// comparison with the needle, so that custom matchers can decide what
+    /// counts as a match. Every candidate leaves room for a full needle, i.e.
+    /// `candidate + needle.len() <= haystack.len()`.
+    #[target_feature(enable = "simd128")]
+    pub unsafe fn search_candidates_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut verify: F,
+    ) -> bool {
+        self.search_filtered_in(haystack, false, &mut verify)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Wasm32Searcher;
+    use crate::WordBoundary;
 
     impl crate::tests::TestSearcher for Wasm32Searcher<&[u8]> {
-        fn with_position(needle: &'static [u8], position: usize) -> Wasm32Searcher<&[u8]> {
+        fn with_position(needle: &'static [u8], position: usize) -> Self {
             unsafe { Wasm32Searcher::with_position(needle, position) }
         }
 
         fn search_in(&self, haystack: &[u8]) -> bool {
             unsafe { Wasm32Searcher::search_in(self, haystack) }
         }
+
+        fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+            unsafe { Wasm32Searcher::search_word_in(self, haystack, boundary) }
+        }
+
+        fn search_candidates_in(
+            &self,
+            haystack: &[u8],
+            verify: &mut dyn FnMut(usize) -> bool,
+        ) -> bool {
+            unsafe { Wasm32Searcher::search_candidates_in(self, haystack, verify) }
+        }
     }
 
     crate::generate_tests!(wasm32_searcher, Wasm32Searcher);
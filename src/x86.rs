@@ -16,12 +16,15 @@
 
 #![allow(clippy::missing_safety_doc)]
 
-use crate::{MemchrSearcher, Needle, NeedleWithSize, Searcher, Vector, VectorHash};
-use seq_macro::seq;
+use crate::{
+    MemchrSearcher, Needle, NeedleWithSize, Searcher, SearcherError, Vector, VectorHash,
+    WordBoundary,
+};
 #[cfg(target_arch = "x86")]
-use std::arch::x86::*;
+use core::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
-use std::arch::x86_64::*;
+use core::arch::x86_64::*;
+use seq_macro::seq;
 
 #[derive(Clone, Copy)]
 #[repr(transparent)]
@@ -41,7 +44,7 @@ impl Vector for __m16i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn load(a: *const u8) -> Self {
-        __m16i(_mm_set1_epi16(std::ptr::read_unaligned(a as *const i16)))
+        __m16i(_mm_set1_epi16(core::ptr::read_unaligned(a as *const i16)))
     }
 
     #[inline]
@@ -59,7 +62,7 @@ impl Vector for __m16i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn to_bitmask(a: Self) -> u32 {
-        std::mem::transmute(_mm_movemask_epi8(a.0) & 0x3)
+        (_mm_movemask_epi8(a.0) & 0x3) as u32
     }
 }
 
@@ -88,7 +91,7 @@ impl Vector for __m32i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn load(a: *const u8) -> Self {
-        __m32i(_mm_set1_epi32(std::ptr::read_unaligned(a as *const i32)))
+        __m32i(_mm_set1_epi32(core::ptr::read_unaligned(a as *const i32)))
     }
 
     #[inline]
@@ -106,7 +109,7 @@ impl Vector for __m32i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn to_bitmask(a: Self) -> u32 {
-        std::mem::transmute(_mm_movemask_epi8(a.0) & 0xF)
+        (_mm_movemask_epi8(a.0) & 0xF) as u32
     }
 }
 
@@ -135,7 +138,7 @@ impl Vector for __m64i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn load(a: *const u8) -> Self {
-        __m64i(_mm_set1_epi64x(std::ptr::read_unaligned(a as *const i64)))
+        __m64i(_mm_set1_epi64x(core::ptr::read_unaligned(a as *const i64)))
     }
 
     #[inline]
@@ -153,7 +156,7 @@ impl Vector for __m64i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn to_bitmask(a: Self) -> u32 {
-        std::mem::transmute(_mm_movemask_epi8(a.0) & 0xFF)
+        (_mm_movemask_epi8(a.0) & 0xFF) as u32
     }
 }
 
@@ -195,7 +198,7 @@ impl Vector for __m128i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn to_bitmask(a: Self) -> u32 {
-        std::mem::transmute(_mm_movemask_epi8(a))
+        _mm_movemask_epi8(a) as u32
     }
 }
 
@@ -230,7 +233,7 @@ impl Vector for __m256i {
     #[inline]
     #[target_feature(enable = "avx2")]
     unsafe fn to_bitmask(a: Self) -> u32 {
-        std::mem::transmute(_mm256_movemask_epi8(a))
+        _mm256_movemask_epi8(a) as u32
     }
 }
 
@@ -295,67 +298,156 @@ impl<N: Needle> Avx2Searcher<N> {
     /// actual size of `needle`.
     #[target_feature(enable = "avx2")]
     pub unsafe fn with_position(needle: N, position: usize) -> Self {
-        // Implicitly checks that the needle is not empty because position is an
-        // unsized integer.
-        assert!(position < needle.size());
+        match Self::try_with_position(needle, position) {
+            Ok(searcher) => searcher,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Same as `new` but returns an error instead of panicking if `needle` is
+    /// invalid.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn try_new(needle: N) -> Result<Self, SearcherError> {
+        // Wrapping prevents panicking on unsigned integer underflow when
+        // `needle` is empty.
+        let position = needle.size().wrapping_sub(1);
+        Self::try_with_position(needle, position)
+    }
+
+    /// Same as `with_position` but returns an error instead of panicking if
+    /// `needle` or `position` is invalid.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn try_with_position(needle: N, position: usize) -> Result<Self, SearcherError> {
+        SearcherError::check(&needle, position)?;
 
         let bytes = needle.as_bytes();
-        if let Some(size) = N::SIZE {
-            assert_eq!(size, bytes.len());
-        }
 
         let sse2_hash = VectorHash::new(bytes[0], bytes[position]);
         let avx2_hash = VectorHash::new(bytes[0], bytes[position]);
 
-        Self {
+        Ok(Self {
             position,
             sse2_hash,
             avx2_hash,
             needle,
-        }
+        })
     }
 
     #[inline]
     #[target_feature(enable = "avx2")]
-    unsafe fn sse2_2_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn sse2_2_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = VectorHash::<__m16i>::from(&self.sse2_hash);
-        crate::vector_search_in_avx2_version(self.needle(), self.position(), haystack, end, &hash)
+        crate::vector_search_in_avx2_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            &hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
     #[target_feature(enable = "avx2")]
-    unsafe fn sse2_4_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn sse2_4_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = VectorHash::<__m32i>::from(&self.sse2_hash);
-        crate::vector_search_in_avx2_version(self.needle(), self.position(), haystack, end, &hash)
+        crate::vector_search_in_avx2_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            &hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
     #[target_feature(enable = "avx2")]
-    unsafe fn sse2_8_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn sse2_8_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = VectorHash::<__m64i>::from(&self.sse2_hash);
-        crate::vector_search_in_avx2_version(self.needle(), self.position(), haystack, end, &hash)
+        crate::vector_search_in_avx2_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            &hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
     #[target_feature(enable = "avx2")]
-    unsafe fn sse2_16_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn sse2_16_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = &self.sse2_hash;
-        crate::vector_search_in_avx2_version(self.needle(), self.position(), haystack, end, hash)
+        crate::vector_search_in_avx2_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            hash,
+            verify,
+            f,
+        )
     }
 
     #[inline]
     #[target_feature(enable = "avx2")]
-    unsafe fn avx2_search_in(&self, haystack: &[u8], end: usize) -> bool {
+    unsafe fn avx2_search_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        end: usize,
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         let hash = &self.avx2_hash;
-        crate::vector_search_in_avx2_version(self.needle(), self.position(), haystack, end, hash)
+        crate::vector_search_in_avx2_version(
+            self.needle(),
+            self.position(),
+            haystack,
+            end,
+            hash,
+            verify,
+            f,
+        )
     }
 
-    /// Inlined version of `search_in` for hot call sites.
     #[inline]
     #[target_feature(enable = "avx2")]
-    pub unsafe fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+    unsafe fn search_filtered_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        verify: bool,
+        f: &mut F,
+    ) -> bool {
         if haystack.len() <= self.needle.size() {
-            return haystack == self.needle.as_bytes();
+            return crate::short_search_in(&self.needle, self.position, haystack, verify, f);
         }
 
         let end = haystack.len() - self.needle.size() + 1;
@@ -363,23 +455,80 @@ impl<N: Needle> Avx2Searcher<N> {
         if end < __m16i::LANES {
             unreachable!();
         } else if end < __m32i::LANES {
-            self.sse2_2_search_in(haystack, end)
+            self.sse2_2_search_in(haystack, end, verify, f)
         } else if end < __m64i::LANES {
-            self.sse2_4_search_in(haystack, end)
+            self.sse2_4_search_in(haystack, end, verify, f)
         } else if end < __m128i::LANES {
-            self.sse2_8_search_in(haystack, end)
+            self.sse2_8_search_in(haystack, end, verify, f)
         } else if end < __m256i::LANES {
-            self.sse2_16_search_in(haystack, end)
+            self.sse2_16_search_in(haystack, end, verify, f)
         } else {
-            self.avx2_search_in(haystack, end)
+            self.avx2_search_in(haystack, end, verify, f)
         }
     }
 
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn search_matches_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        self.search_filtered_in(haystack, true, f)
+    }
+
+    /// Inlined version of `search_in` for hot call sites.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn inlined_search_in(&self, haystack: &[u8]) -> bool {
+        self.search_matches_in(haystack, &mut |_| true)
+    }
+
     /// Performs a substring search for the `needle` within `haystack`.
     #[target_feature(enable = "avx2")]
     pub unsafe fn search_in(&self, haystack: &[u8]) -> bool {
         self.inlined_search_in(haystack)
     }
+
+    /// Searches each of `haystacks` in turn with AVX2 enabled for the whole
+    /// batch, so that `inlined_search_in` is inlined into the loop.
+    #[target_feature(enable = "avx2")]
+    unsafe fn batch_search_in<'h, I: ExactSizeIterator<Item = &'h [u8]>>(
+        &self,
+        haystacks: I,
+        matches: &mut [u64],
+    ) {
+        crate::batch_search_in(haystacks, matches, |haystack| {
+            self.inlined_search_in(haystack)
+        })
+    }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        let size = self.needle.size();
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, size)
+        })
+    }
+
+    /// Calls `verify` with every candidate position of `haystack` at which
+    /// both the first byte of the needle and the byte at `position` match, in
+    /// increasing order, stopping as soon as `verify` returns `true`. Returns
+    /// whether any candidate was accepted.
+    ///
+    /// This exposes the SIMD filter used by `search_in` without the final
+    /// comparison with the needle, so that custom matchers can decide what
+    /// counts as a match. Every candidate leaves room for a full needle, i.e.
+    /// `candidate + needle.len() <= haystack.len()`.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn search_candidates_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut verify: F,
+    ) -> bool {
+        self.search_filtered_in(haystack, false, &mut verify)
+    }
 }
 
 impl<N: Needle> Searcher<N> for Avx2Searcher<N> {
@@ -394,6 +543,41 @@ impl<N: Needle> Searcher<N> for Avx2Searcher<N> {
     }
 }
 
+// Constructing an `Avx2Searcher` requires AVX2 support, which guarantees that
+// its methods can be safely called from the trait.
+impl<N: Needle> crate::SubstringSearch for Avx2Searcher<N> {
+    fn needle(&self) -> &[u8] {
+        self.needle.as_bytes()
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        unsafe { self.inlined_search_in(haystack) }
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let mut found = None;
+        unsafe {
+            self.search_matches_in(haystack, &mut |position| {
+                found = Some(position);
+                true
+            })
+        };
+        found
+    }
+
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        unsafe { self.batch_search_in(haystacks.iter().copied(), matches) }
+    }
+
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        unsafe { self.batch_search_in(crate::offsets_haystacks(offsets, data), matches) }
+    }
+}
+
 /// Single-substring searcher based on `Avx2Searcher` but with dynamic algorithm
 /// selection.
 ///
@@ -466,29 +650,117 @@ impl<N: Needle> DynamicAvx2Searcher<N> {
     /// for `needle`.
     #[target_feature(enable = "avx2")]
     pub unsafe fn with_position(needle: N, position: usize) -> Self {
-        match *needle.as_bytes() {
-            [] => Self::N0,
+        // Any `position` is accepted for empty needles, unlike with
+        // `try_with_position`.
+        if needle.as_bytes().is_empty() {
+            return Self::N0;
+        }
+
+        match Self::try_with_position(needle, position) {
+            Ok(searcher) => searcher,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    /// Same as `new` but returns an error instead of panicking if the `SIZE`
+    /// constant associated with `needle` is invalid.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn try_new(needle: N) -> Result<Self, SearcherError> {
+        // Saturating sets `position` to zero when `needle` is empty, which is
+        // the only valid position for it.
+        let position = needle.as_bytes().len().saturating_sub(1);
+        Self::try_with_position(needle, position)
+    }
+
+    /// Same as `with_position` but returns an error instead of panicking if
+    /// `position` is invalid. Unlike other searchers, empty needles are
+    /// accepted, with `position` set to zero.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn try_with_position(needle: N, position: usize) -> Result<Self, SearcherError> {
+        Ok(match *needle.as_bytes() {
+            [] => {
+                // Check that `position` is set correctly for consistency.
+                if position != 0 {
+                    return Err(SearcherError::PositionOutOfRange { position, size: 0 });
+                }
+                Self::N0
+            }
             [c0] => {
                 // Check that `position` is set correctly for consistency.
-                assert_eq!(position, 0);
+                if position != 0 {
+                    return Err(SearcherError::PositionOutOfRange { position, size: 1 });
+                }
                 Self::N1(MemchrSearcher::new(c0))
             }
-            array!(c, 2) => Self::N2(Avx2Searcher::with_position(array!(c, 2), position)),
-            array!(c, 3) => Self::N3(Avx2Searcher::with_position(array!(c, 3), position)),
-            array!(c, 4) => Self::N4(Avx2Searcher::with_position(array!(c, 4), position)),
-            array!(c, 5) => Self::N5(Avx2Searcher::with_position(array!(c, 5), position)),
-            array!(c, 6) => Self::N6(Avx2Searcher::with_position(array!(c, 6), position)),
-            array!(c, 7) => Self::N7(Avx2Searcher::with_position(array!(c, 7), position)),
-            array!(c, 8) => Self::N8(Avx2Searcher::with_position(array!(c, 8), position)),
-            array!(c, 9) => Self::N9(Avx2Searcher::with_position(array!(c, 9), position)),
-            array!(c, 10) => Self::N10(Avx2Searcher::with_position(array!(c, 10), position)),
-            array!(c, 11) => Self::N11(Avx2Searcher::with_position(array!(c, 11), position)),
-            array!(c, 12) => Self::N12(Avx2Searcher::with_position(array!(c, 12), position)),
-            array!(c, 13) => Self::N13(Avx2Searcher::with_position(array!(c, 13), position)),
-            array!(c, 14) => Self::N14(Avx2Searcher::with_position(array!(c, 14), position)),
-            array!(c, 15) => Self::N15(Avx2Searcher::with_position(array!(c, 15), position)),
-            array!(c, 16) => Self::N16(Avx2Searcher::with_position(array!(c, 16), position)),
-            _ => Self::N(Avx2Searcher::with_position(needle, position)),
+            array!(c, 2) => Self::N2(Avx2Searcher::try_with_position(array!(c, 2), position)?),
+            array!(c, 3) => Self::N3(Avx2Searcher::try_with_position(array!(c, 3), position)?),
+            array!(c, 4) => Self::N4(Avx2Searcher::try_with_position(array!(c, 4), position)?),
+            array!(c, 5) => Self::N5(Avx2Searcher::try_with_position(array!(c, 5), position)?),
+            array!(c, 6) => Self::N6(Avx2Searcher::try_with_position(array!(c, 6), position)?),
+            array!(c, 7) => Self::N7(Avx2Searcher::try_with_position(array!(c, 7), position)?),
+            array!(c, 8) => Self::N8(Avx2Searcher::try_with_position(array!(c, 8), position)?),
+            array!(c, 9) => Self::N9(Avx2Searcher::try_with_position(array!(c, 9), position)?),
+            array!(c, 10) => Self::N10(Avx2Searcher::try_with_position(array!(c, 10), position)?),
+            array!(c, 11) => Self::N11(Avx2Searcher::try_with_position(array!(c, 11), position)?),
+            array!(c, 12) => Self::N12(Avx2Searcher::try_with_position(array!(c, 12), position)?),
+            array!(c, 13) => Self::N13(Avx2Searcher::try_with_position(array!(c, 13), position)?),
+            array!(c, 14) => Self::N14(Avx2Searcher::try_with_position(array!(c, 14), position)?),
+            array!(c, 15) => Self::N15(Avx2Searcher::try_with_position(array!(c, 15), position)?),
+            array!(c, 16) => Self::N16(Avx2Searcher::try_with_position(array!(c, 16), position)?),
+            _ => Self::N(Avx2Searcher::try_with_position(needle, position)?),
+        })
+    }
+
+    #[inline]
+    fn needle_size(&self) -> usize {
+        match self {
+            Self::N0 => 0,
+            Self::N1(_) => 1,
+            Self::N2(_) => 2,
+            Self::N3(_) => 3,
+            Self::N4(_) => 4,
+            Self::N5(_) => 5,
+            Self::N6(_) => 6,
+            Self::N7(_) => 7,
+            Self::N8(_) => 8,
+            Self::N9(_) => 9,
+            Self::N10(_) => 10,
+            Self::N11(_) => 11,
+            Self::N12(_) => 12,
+            Self::N13(_) => 13,
+            Self::N14(_) => 14,
+            Self::N15(_) => 15,
+            Self::N16(_) => 16,
+            Self::N(searcher) => searcher.needle.size(),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn search_matches_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        f: &mut F,
+    ) -> bool {
+        match self {
+            Self::N0 => (0..=haystack.len()).any(f),
+            Self::N1(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N2(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N3(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N4(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N5(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N6(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N7(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N8(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N9(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N10(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N11(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N12(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N13(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N14(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N15(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N16(searcher) => searcher.search_matches_in(haystack, f),
+            Self::N(searcher) => searcher.search_matches_in(haystack, f),
         }
     }
 
@@ -523,12 +795,155 @@ impl<N: Needle> DynamicAvx2Searcher<N> {
     pub unsafe fn search_in(&self, haystack: &[u8]) -> bool {
         self.inlined_search_in(haystack)
     }
+
+    /// Same as `Avx2Searcher::batch_search_in`, dispatching on the length of
+    /// the needle once for the whole batch.
+    #[target_feature(enable = "avx2")]
+    unsafe fn batch_search_in<'h, I: ExactSizeIterator<Item = &'h [u8]>>(
+        &self,
+        haystacks: I,
+        matches: &mut [u64],
+    ) {
+        match self {
+            Self::N0 => crate::batch_search_in(haystacks, matches, |_| true),
+            Self::N1(searcher) => crate::batch_search_in(haystacks, matches, |haystack| {
+                searcher.inlined_search_in(haystack)
+            }),
+            Self::N2(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N3(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N4(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N5(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N6(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N7(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N8(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N9(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N10(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N11(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N12(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N13(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N14(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N15(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N16(searcher) => searcher.batch_search_in(haystacks, matches),
+            Self::N(searcher) => searcher.batch_search_in(haystacks, matches),
+        }
+    }
+
+    /// Same as `search_in` but only considers matches delimited by `boundary`.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+        let size = self.needle_size();
+        self.search_matches_in(haystack, &mut |position| {
+            boundary.is_match(haystack, position, size)
+        })
+    }
+
+    /// Same as `Avx2Searcher::search_candidates_in`. Since needles of length
+    /// zero and one are not filtered, all of their matches are candidates.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn search_candidates_in<F: FnMut(usize) -> bool>(
+        &self,
+        haystack: &[u8],
+        mut verify: F,
+    ) -> bool {
+        match self {
+            Self::N0 => (0..=haystack.len()).any(verify),
+            Self::N1(searcher) => searcher.search_matches_in(haystack, &mut verify),
+            Self::N2(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N3(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N4(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N5(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N6(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N7(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N8(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N9(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N10(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N11(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N12(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N13(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N14(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N15(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N16(searcher) => searcher.search_candidates_in(haystack, verify),
+            Self::N(searcher) => searcher.search_candidates_in(haystack, verify),
+        }
+    }
+}
+
+// Constructing a `DynamicAvx2Searcher` requires AVX2 support, which guarantees
+// that its methods can be safely called from the trait.
+impl<N: Needle> crate::SubstringSearch for DynamicAvx2Searcher<N> {
+    fn needle(&self) -> &[u8] {
+        match self {
+            Self::N0 => &[],
+            Self::N1(searcher) => crate::SubstringSearch::needle(searcher),
+            Self::N2(searcher) => searcher.needle.as_bytes(),
+            Self::N3(searcher) => searcher.needle.as_bytes(),
+            Self::N4(searcher) => searcher.needle.as_bytes(),
+            Self::N5(searcher) => searcher.needle.as_bytes(),
+            Self::N6(searcher) => searcher.needle.as_bytes(),
+            Self::N7(searcher) => searcher.needle.as_bytes(),
+            Self::N8(searcher) => searcher.needle.as_bytes(),
+            Self::N9(searcher) => searcher.needle.as_bytes(),
+            Self::N10(searcher) => searcher.needle.as_bytes(),
+            Self::N11(searcher) => searcher.needle.as_bytes(),
+            Self::N12(searcher) => searcher.needle.as_bytes(),
+            Self::N13(searcher) => searcher.needle.as_bytes(),
+            Self::N14(searcher) => searcher.needle.as_bytes(),
+            Self::N15(searcher) => searcher.needle.as_bytes(),
+            Self::N16(searcher) => searcher.needle.as_bytes(),
+            Self::N(searcher) => searcher.needle.as_bytes(),
+        }
+    }
+
+    fn position(&self) -> usize {
+        match self {
+            Self::N0 | Self::N1(_) => 0,
+            Self::N2(searcher) => searcher.position,
+            Self::N3(searcher) => searcher.position,
+            Self::N4(searcher) => searcher.position,
+            Self::N5(searcher) => searcher.position,
+            Self::N6(searcher) => searcher.position,
+            Self::N7(searcher) => searcher.position,
+            Self::N8(searcher) => searcher.position,
+            Self::N9(searcher) => searcher.position,
+            Self::N10(searcher) => searcher.position,
+            Self::N11(searcher) => searcher.position,
+            Self::N12(searcher) => searcher.position,
+            Self::N13(searcher) => searcher.position,
+            Self::N14(searcher) => searcher.position,
+            Self::N15(searcher) => searcher.position,
+            Self::N16(searcher) => searcher.position,
+            Self::N(searcher) => searcher.position,
+        }
+    }
+
+    fn search_in(&self, haystack: &[u8]) -> bool {
+        unsafe { self.inlined_search_in(haystack) }
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let mut found = None;
+        unsafe {
+            self.search_matches_in(haystack, &mut |position| {
+                found = Some(position);
+                true
+            })
+        };
+        found
+    }
+
+    fn search_batch_in(&self, haystacks: &[&[u8]], matches: &mut [u64]) {
+        unsafe { self.batch_search_in(haystacks.iter().copied(), matches) }
+    }
+
+    fn search_offsets_in(&self, offsets: &[usize], data: &[u8], matches: &mut [u64]) {
+        unsafe { self.batch_search_in(crate::offsets_haystacks(offsets, data), matches) }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{Avx2Searcher, DynamicAvx2Searcher};
-    use crate::Needle;
+    use crate::{Needle, SearcherError, WordBoundary};
 
     #[test]
     #[should_panic]
@@ -564,6 +979,90 @@ mod tests {
         unsafe { Avx2Searcher::new(Foo(b"foo")) };
     }
 
+    #[test]
+    fn avx2_try_new() {
+        struct Foo(&'static [u8]);
+
+        impl Needle for Foo {
+            const SIZE: Option<usize> = Some(2);
+
+            fn as_bytes(&self) -> &[u8] {
+                self.0
+            }
+        }
+
+        unsafe {
+            assert!(Avx2Searcher::try_new(&b"foo"[..]).is_ok());
+            assert!(Avx2Searcher::try_with_position(&b"foo"[..], 2).is_ok());
+
+            assert_eq!(
+                Avx2Searcher::try_new(&b""[..]).err(),
+                Some(SearcherError::EmptyNeedle)
+            );
+            assert_eq!(
+                Avx2Searcher::try_with_position(&b"foo"[..], 3).err(),
+                Some(SearcherError::PositionOutOfRange {
+                    position: 3,
+                    size: 3
+                })
+            );
+            assert_eq!(
+                Avx2Searcher::try_new(Foo(b"foo")).err(),
+                Some(SearcherError::SizeMismatch {
+                    expected: 2,
+                    actual: 3
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn dynamic_avx2_try_with_position() {
+        unsafe {
+            assert!(DynamicAvx2Searcher::try_new(&b""[..]).is_ok());
+            assert!(DynamicAvx2Searcher::try_with_position(&b""[..], 0).is_ok());
+            assert!(DynamicAvx2Searcher::try_new(&b"f"[..]).is_ok());
+            assert!(DynamicAvx2Searcher::try_with_position(&b"f"[..], 0).is_ok());
+            assert!(DynamicAvx2Searcher::try_with_position(&b"foobar"[..], 5).is_ok());
+
+            assert_eq!(
+                DynamicAvx2Searcher::try_with_position(&b""[..], 1).err(),
+                Some(SearcherError::PositionOutOfRange {
+                    position: 1,
+                    size: 0
+                })
+            );
+            assert_eq!(
+                DynamicAvx2Searcher::try_with_position(&b""[..], usize::MAX).err(),
+                Some(SearcherError::PositionOutOfRange {
+                    position: usize::MAX,
+                    size: 0
+                })
+            );
+            assert_eq!(
+                DynamicAvx2Searcher::try_with_position(&b"f"[..], 1).err(),
+                Some(SearcherError::PositionOutOfRange {
+                    position: 1,
+                    size: 1
+                })
+            );
+            assert_eq!(
+                DynamicAvx2Searcher::try_with_position(&b"foo"[..], 3).err(),
+                Some(SearcherError::PositionOutOfRange {
+                    position: 3,
+                    size: 3
+                })
+            );
+            assert_eq!(
+                DynamicAvx2Searcher::try_with_position(&b"foobarbazquxquux"[..], 16).err(),
+                Some(SearcherError::PositionOutOfRange {
+                    position: 16,
+                    size: 16
+                })
+            );
+        }
+    }
+
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn size_of_avx2_searcher() {
@@ -587,25 +1086,49 @@ mod tests {
     }
 
     impl crate::tests::TestSearcher for Avx2Searcher<&[u8]> {
-        fn with_position(needle: &'static [u8], position: usize) -> Avx2Searcher<&[u8]> {
+        fn with_position(needle: &'static [u8], position: usize) -> Self {
             unsafe { Avx2Searcher::with_position(needle, position) }
         }
 
         fn search_in(&self, haystack: &[u8]) -> bool {
             unsafe { Avx2Searcher::search_in(self, haystack) }
         }
+
+        fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+            unsafe { Avx2Searcher::search_word_in(self, haystack, boundary) }
+        }
+
+        fn search_candidates_in(
+            &self,
+            haystack: &[u8],
+            verify: &mut dyn FnMut(usize) -> bool,
+        ) -> bool {
+            unsafe { Avx2Searcher::search_candidates_in(self, haystack, verify) }
+        }
     }
 
     crate::generate_tests!(avx2_searcher, Avx2Searcher);
 
     impl crate::tests::TestSearcher for DynamicAvx2Searcher<&[u8]> {
-        fn with_position(needle: &'static [u8], position: usize) -> DynamicAvx2Searcher<&[u8]> {
+        fn with_position(needle: &'static [u8], position: usize) -> Self {
             unsafe { DynamicAvx2Searcher::with_position(needle, position) }
         }
 
         fn search_in(&self, haystack: &[u8]) -> bool {
             unsafe { DynamicAvx2Searcher::search_in(self, haystack) }
         }
+
+        fn search_word_in(&self, haystack: &[u8], boundary: &WordBoundary) -> bool {
+            unsafe { DynamicAvx2Searcher::search_word_in(self, haystack, boundary) }
+        }
+
+        fn search_candidates_in(
+            &self,
+            haystack: &[u8],
+            verify: &mut dyn FnMut(usize) -> bool,
+        ) -> bool {
+            unsafe { DynamicAvx2Searcher::search_candidates_in(self, haystack, verify) }
+        }
     }
 
     crate::generate_tests!(dynamic_avx2_searcher, DynamicAvx2Searcher);
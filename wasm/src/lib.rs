@@ -0,0 +1,121 @@
+//! WebAssembly exports for the sliceslice substring search library, allowing
+//! hosts to search without writing Rust.
+//!
+//! Buffers are passed through the linear memory of the module: the host
+//! allocates them with `ss_alloc`, writes the needle or haystack into them,
+//! passes their pointer and length to the other functions and finally releases
+//! them with `ss_dealloc`. Searchers are opaque handles returned by
+//! `ss_create` and destroyed with `ss_free`.
+//!
+//! When built for `wasm32-wasip1` with the `simd128` target feature, as
+//! configured in `.cargo/config.toml`, needles of two bytes or more are
+//! searched using a `Wasm32Searcher`.
+
+use sliceslice::memmem::Finder;
+use sliceslice::SubstringSearch;
+use std::alloc::{self, Layout};
+use std::slice;
+
+/// Searcher handle returned by `ss_create`.
+pub struct Searcher {
+    finder: Finder<'static>,
+}
+
+/// Returns a slice from a pointer and a length, allowing null pointers for
+/// empty slices.
+unsafe fn bytes<'a>(pointer: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(pointer, len)
+    }
+}
+
+/// Allocates a buffer of `len` bytes in the linear memory, to be released
+/// with `ss_dealloc`. Returns null if `len` is zero or the allocation failed.
+#[no_mangle]
+pub extern "C" fn ss_alloc(len: usize) -> *mut u8 {
+    match Layout::array::<u8>(len) {
+        Ok(layout) if len > 0 => unsafe { alloc::alloc(layout) },
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// Releases a buffer of `len` bytes returned by `ss_alloc`.
+///
+/// # Safety
+///
+/// `pointer` must have been returned by `ss_alloc(len)` and not released yet,
+/// or be null.
+#[no_mangle]
+pub unsafe extern "C" fn ss_dealloc(pointer: *mut u8, len: usize) {
+    if !pointer.is_null() {
+        alloc::dealloc(pointer, Layout::array::<u8>(len).unwrap());
+    }
+}
+
+/// Creates a new searcher for the `needle_len` bytes at `needle`, which are
+/// copied so that the buffer can be released right away.
+///
+/// # Safety
+///
+/// `needle` must point to `needle_len` readable bytes, or may be null if
+/// `needle_len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn ss_create(needle: *const u8, needle_len: usize) -> *mut Searcher {
+    let searcher = Searcher {
+        finder: Finder::new(bytes(needle, needle_len)).into_owned(),
+    };
+    Box::into_raw(Box::new(searcher))
+}
+
+/// Returns 1 if the `haystack_len` bytes at `haystack` contain the needle of
+/// `searcher`, or 0 otherwise.
+///
+/// # Safety
+///
+/// `searcher` must have been returned by `ss_create` and not freed yet.
+/// `haystack` must point to `haystack_len` readable bytes, or may be null if
+/// `haystack_len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn ss_search(
+    searcher: *const Searcher,
+    haystack: *const u8,
+    haystack_len: usize,
+) -> u32 {
+    (*searcher).finder.search_in(bytes(haystack, haystack_len)) as u32
+}
+
+/// Returns the position of the leftmost match of the needle of `searcher`
+/// within the `haystack_len` bytes at `haystack`, or -1 if there is none.
+///
+/// # Safety
+///
+/// `searcher` must have been returned by `ss_create` and not freed yet.
+/// `haystack` must point to `haystack_len` readable bytes, or may be null if
+/// `haystack_len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn ss_find(
+    searcher: *const Searcher,
+    haystack: *const u8,
+    haystack_len: usize,
+) -> i64 {
+    match (*searcher).finder.find(bytes(haystack, haystack_len)) {
+        Some(position) => position as i64,
+        None => -1,
+    }
+}
+
+/// Destroys a searcher returned by `ss_create`. Does nothing if `searcher` is
+/// null.
+///
+/// # Safety
+///
+/// `searcher` must have been returned by `ss_create` and not freed yet, or be
+/// null.
+#[no_mangle]
+pub unsafe extern "C" fn ss_free(searcher: *mut Searcher) {
+    if !searcher.is_null() {
+        drop(Box::from_raw(searcher));
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use wasmtime::{Engine, Instance, Linker, Memory, Module, Store, TypedFunc};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::WasiCtxBuilder;
+
+static I386: &[u8] = include_bytes!("../../data/i386.txt");
+static WORDS: &[u8] = include_bytes!("../../data/words.txt");
+
+/// Builds the module for `wasm32-wasip1`, once for all the tests, in a separate
+/// target directory so as not to contend with the build of the tests.
+fn module_path() -> &'static PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let target_dir = manifest_dir.join("target").join("module");
+
+        let status = Command::new(env!("CARGO"))
+            .current_dir(&manifest_dir)
+            .args(["build", "--release", "--target", "wasm32-wasip1"])
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "failed to build the wasm32-wasip1 module");
+
+        target_dir.join("wasm32-wasip1/release/sliceslice_wasm.wasm")
+    })
+}
+
+/// Instance of the module, with typed wrappers around its exports.
+struct Host {
+    store: Store<WasiP1Ctx>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+    dealloc: TypedFunc<(u32, u32), ()>,
+    create: TypedFunc<(u32, u32), u32>,
+    search: TypedFunc<(u32, u32, u32), u32>,
+    find: TypedFunc<(u32, u32, u32), i64>,
+    free: TypedFunc<u32, ()>,
+}
+
+impl Host {
+    fn new() -> Self {
+        let engine = Engine::default();
+        let module = Module::from_file(&engine, module_path()).unwrap();
+
+        let mut linker = Linker::new(&engine);
+        preview1::add_to_linker_sync(&mut linker, |ctx| ctx).unwrap();
+        let mut store = Store::new(&engine, WasiCtxBuilder::new().build_p1());
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+
+        // Reactor modules must be initialized before calling their exports.
+        if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+            initialize.call(&mut store, ()).unwrap();
+        }
+
+        fn export<P: wasmtime::WasmParams, R: wasmtime::WasmResults>(
+            instance: &Instance,
+            store: &mut Store<WasiP1Ctx>,
+            name: &str,
+        ) -> TypedFunc<P, R> {
+            instance.get_typed_func(store, name).unwrap()
+        }
+
+        Self {
+            memory: instance.get_memory(&mut store, "memory").unwrap(),
+            alloc: export(&instance, &mut store, "ss_alloc"),
+            dealloc: export(&instance, &mut store, "ss_dealloc"),
+            create: export(&instance, &mut store, "ss_create"),
+            search: export(&instance, &mut store, "ss_search"),
+            find: export(&instance, &mut store, "ss_find"),
+            free: export(&instance, &mut store, "ss_free"),
+            store,
+        }
+    }
+
+    /// Copies `bytes` into the linear memory of the module, calls `f` with
+    /// their pointer and length, then releases them.
+    fn with_bytes<T>(&mut self, bytes: &[u8], f: impl FnOnce(&mut Self, u32, u32) -> T) -> T {
+        let len = u32::try_from(bytes.len()).unwrap();
+        let pointer = self.alloc.call(&mut self.store, len).unwrap();
+        assert!(pointer != 0 || len == 0);
+        self.memory
+            .write(&mut self.store, pointer as usize, bytes)
+            .unwrap();
+
+        let result = f(self, pointer, len);
+
+        self.dealloc.call(&mut self.store, (pointer, len)).unwrap();
+        result
+    }
+
+    fn create(&mut self, needle: &[u8]) -> u32 {
+        self.with_bytes(needle, |host, pointer, len| {
+            host.create.call(&mut host.store, (pointer, len)).unwrap()
+        })
+    }
+
+    fn search(&mut self, searcher: u32, haystack: &[u8]) -> bool {
+        self.with_bytes(haystack, |host, pointer, len| {
+            host.search
+                .call(&mut host.store, (searcher, pointer, len))
+                .unwrap()
+                != 0
+        })
+    }
+
+    fn find(&mut self, searcher: u32, haystack: &[u8]) -> Option<usize> {
+        self.with_bytes(haystack, |host, pointer, len| {
+            let position = host
+                .find
+                .call(&mut host.store, (searcher, pointer, len))
+                .unwrap();
+            usize::try_from(position).ok()
+        })
+    }
+
+    fn free(&mut self, searcher: u32) {
+        self.free.call(&mut self.store, searcher).unwrap();
+    }
+}
+
+fn search(host: &mut Host, haystack: &[u8], needles: &[&[u8]]) {
+    for &needle in needles {
+        let searcher = host.create(needle);
+        let expected = memchr::memmem::find(haystack, needle);
+
+        assert_eq!(host.find(searcher, haystack), expected, "{needle:?}");
+        assert_eq!(host.search(searcher, haystack), expected.is_some());
+
+        host.free(searcher);
+    }
+}
+
+#[test]
+fn host_search() {
+    let mut host = Host::new();
+
+    let haystacks: [&[u8]; 6] = [
+        b"",
+        b"a",
+        b"aaa",
+        b"abab",
+        b"azbc",
+        b"zzzzzzzzzzzzzzzzabczdef",
+    ];
+    let needles: [&[u8]; 7] = [b"", b"a", b"aa", b"ab", b"abc", b"abczdef", b"zzz"];
+
+    for haystack in haystacks {
+        search(&mut host, haystack, &needles);
+    }
+}
+
+#[test]
+fn host_search_words() {
+    let mut host = Host::new();
+
+    let needles: Vec<&[u8]> = WORDS.split(|&b| b == b'\n').step_by(1000).collect();
+    search(&mut host, WORDS, &needles);
+    search(&mut host, WORDS, &[b"ation\nab", b"zzzzzz", b"\n\n"]);
+}
+
+#[test]
+fn host_search_i386() {
+    let mut host = Host::new();
+
+    let needles: [&[u8]; 5] = [
+        b"Intel Architecture",
+        b"instruction",
+        b"MOVSX",
+        b"not a word in the manual",
+        b"\n",
+    ];
+    search(&mut host, I386, &needles);
+}